@@ -8,9 +8,9 @@ fn main() {
     println!("by filter_sum: {}", filter_sum);
 
     let by_triangles = {
-        let n_3_5 = 1000 / (3 * 5);
-        let n_5 = 1000 / 5;
-        let n_3 = 1000 / 3;
+        let n_3_5 = 1000u64 / (3 * 5);
+        let n_5 = 1000u64 / 5;
+        let n_3 = 1000u64 / 3;
 
         triangular(n_3) * 3 +
         triangular(n_5) * 5 -
@@ -1,3 +1,4 @@
+#[allow(clippy::needless_range_loop)]
 fn main() {
     let num_adjacent = 4usize;
     const N: usize = 20;
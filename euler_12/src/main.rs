@@ -24,7 +24,7 @@ use hypatia::numbers::{triangular, Primes};
 fn main() {
     let min_count = 500;
     let mut i = 1u64;
-    let mut ps = Primes::default();
+    let ps = Primes::default();
     loop {
         let tri_i = triangular(i);
         let fs = ps.factorise(tri_i);
@@ -104,15 +104,11 @@ fn main() {
 53503534226472524250874054075591789781264330331690".trim();
 
     let numbers: Vec<Decimal> = numbers_text.lines()
-        .map(|l| Decimal::from_string(l))
+        .map(|l| l.parse().unwrap())
         .collect();
 
-    let sum: Decimal = numbers.iter().fold(Decimal::zero(), |l, r| &l + r);
+    let sum: Decimal = numbers.iter().sum();
 
-    let sum_string = format!("{}", sum);
-
-    let first_10_digits: String = sum_string.chars().take(10).collect();
-
-    println!("Sum: {}", sum_string);
-    println!("First 10 digits: {}", first_10_digits);
+    println!("Sum: {}", sum);
+    println!("First 10 digits: {}", sum.leading_digits(10));
 }
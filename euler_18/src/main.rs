@@ -22,11 +22,7 @@ fn main() {
         .collect();
     
     let mut sums: Vec<Vec<u64>> = Vec::new();
-    sums.push({
-        let mut row: Vec<u64> = Vec::new();
-        row.push(triangle_numbers[0][0]);
-        row
-    });
+    sums.push(vec![triangle_numbers[0][0]]);
 
     // dynamic programming - set [row][col] with max[row-1][col-1, col]
     for r in 1 .. triangle_numbers.len() {
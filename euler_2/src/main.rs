@@ -2,7 +2,7 @@ use hypatia::numbers::fib;
 
 fn main() {
     let even_sum: u64 = 
-        fib().take_while(|&f| f < 4_000_000u64).filter(|&f| f % 2 == 0).sum();
+        fib::<u64>().take_while(|&f| f < 4_000_000).filter(|&f| f % 2 == 0).sum();
     
     println!("even_sum: {}", even_sum);
 }
@@ -1,12 +1,15 @@
 use std::collections::HashSet;
 
-use hypatia::numbers::{Primes, Perfection};
+use rayon::prelude::*;
+
+use hypatia::numbers::Perfection;
+use hypatia::sieve::FactorSieve;
 
 fn main() {
-    let mut primes = Primes::default();
+    let sieve = FactorSieve::up_to(28123);
 
-    let abundants: Vec<u64> = (1..=28123)
-        .map(|i| primes.factorise(i))
+    let abundants: Vec<u64> = (1..=28123u64).into_par_iter()
+        .map(|i| sieve.factorise(i))
         .filter(|f| f.perfection() == Perfection::Abundant)
         .map(|f| f.n)
         .collect();
@@ -26,6 +29,3 @@ fn main() {
 // fixme: this runs embarasingly slowly -- there must be some numerology to speed this up.
 // Firstly, the hashset should be replaced with a bitset.
 // Then - there should be a more clever way to manage the sum.
-//
-// Additinally, factorizations should be memoised
-//  - once you have factorised a number down to one with a known factorisation, short-circuit
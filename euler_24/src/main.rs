@@ -39,13 +39,13 @@ fn main() {
 // By keeping a list of the unused digits in lexographic order, we can then pull out the one at that count.
 // As the digit is used, it is removed from this remaining digits list so that it is not re-used.
 fn perm(p: usize, n: usize) -> Vec<usize> {
-    let mut counter: Vec<usize> = factorial()
+    let mut counter: Vec<usize> = factorial::<usize>()
         .enumerate()
         .map(|(i, f)| {
             // println!("Digit with prefix length {} has {} permutations.", i, f);
             // println!("The {}th count of these is {}", p, p / f);
             // println!("There are only {} alternatives for this digit, so we've counted to alternative {} of these.", {i as u64+1}, (p/f) % (i as u64+1)); 
-            (p / f as usize) % (i+1)}
+            (p / f) % (i+1)}
             )
         .take(n)
         .collect();
@@ -54,12 +54,12 @@ fn perm(p: usize, n: usize) -> Vec<usize> {
     
     // println!("counters in perm order: {:?}", counter);
     let mut digits: Vec<usize> = (0..n).collect();
-    for i in 0..n {
-        let c = counter[i];
+    for slot in counter.iter_mut() {
+        let c = *slot;
         let d = digits[c];
         // println!("Selecting item {} of {:?} as {}", c, digits, d);
         digits.retain(|&x| x != d);
-        counter[i] = d;
+        *slot = d;
     }
 
     // println!("digits: {:?}", counter);
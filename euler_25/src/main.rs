@@ -3,8 +3,9 @@ use hypatia::decimal::{Decimal};
 fn main() {
     println!("First 12 fibs are: {:?}", Decimal::fib().enumerate().take(12).collect::<Vec<_>>());
 
+    // fib() is indexed from 0, the same as the puzzle, so the index needs no adjustment
     let (i, f) = Decimal::fib()
         .enumerate()
-        .filter(|(_, f)| f.digits().len() >= 1000).next().unwrap();
-    println!("First thousand digit fib is {} with {} digits", i+1, f.digits().len());
+        .find(|(_, f)| f.digits().len() >= 1000).unwrap();
+    println!("First thousand digit fib is {} with {} digits", i, f.digits().len());
 }
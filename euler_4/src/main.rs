@@ -1,7 +1,7 @@
 use hypatia::numbers::is_palendrome;
 
 fn main() {
-    let largest = (100..999)
+    let largest = (100..999u32)
         .flat_map(|i| (100..i).map(move |j| (i, j, i*j)))
         .filter(|ijp| is_palendrome(ijp.2)).max_by_key(|ijp| ijp.2)
         .unwrap();
@@ -1,8 +1,7 @@
-use itertools::Itertools;
-use hypatia::numbers::{Factor, Primes};
+use hypatia::numbers::Primes;
 
 fn main() {
-    let mut primes = Primes::default();
+    let primes = Primes::default();
 
     // manually implemented
     // let mut all_factors: Vec<Factor> = (1..=20)
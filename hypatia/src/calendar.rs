@@ -3,6 +3,7 @@ pub struct Year(u16);
 
 #[derive(Clone, Copy)]
 pub struct Month(u8);   // 0=January .. 11=December
+#[allow(dead_code)]
 pub struct Day(u8);     // 0=Sunday .. 6=Saturday
 
 pub const MONTHS: u8 = 12;
@@ -0,0 +1,218 @@
+use crate::decimal::Decimal;
+use crate::numbers::isqrt;
+use crate::rational::Rational;
+
+// A simple continued fraction
+//
+//  a0 + 1/(a1 + 1/(a2 + 1/(a3 + ...)))
+//
+// written [a0; a1, a2, a3, ...].
+//
+// The terms are split into the ones that don't repeat (the prefix, which starts with a0),
+// and then the ones that repeat forever (the period), so that e.g.
+//  sqrt(7) = [2; 1, 1, 1, 4, 1, 1, 1, 4, ...] = [2; (1, 1, 1, 4)]
+// has prefix [2] and period [1, 1, 1, 4].
+// Rationals have a finite expansion, with an empty period, and quadratic irrationals are exactly the ones with a periodic expansion.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContinuedFraction {
+    prefix: Vec<u64>,
+    period: Vec<u64>,
+}
+
+impl ContinuedFraction {
+    pub fn new(prefix: Vec<u64>, period: Vec<u64>) -> ContinuedFraction {
+        ContinuedFraction { prefix, period }
+    }
+
+    // The finite expansion of `numer/denom`, from the quotients of Euclid's algorithm.
+    //
+    // Panics if `denom` is zero.
+    pub fn from_fraction(numer: u64, denom: u64) -> ContinuedFraction {
+        assert!(denom != 0, "attempt to divide by zero");
+        let (mut p, mut q) = (numer, denom);
+        let mut prefix = Vec::new();
+        while q != 0 {
+            prefix.push(p / q);
+            (p, q) = (q, p % q);
+        }
+        ContinuedFraction { prefix, period: Vec::new() }
+    }
+
+    // The expansion of the square root of `d`.
+    //
+    // Write the remainder at each step as `(sqrt(d) + m)/q`, whose integer part is `a = (a0 + m)/q`.
+    // Taking off `a` and flipping gives the next remainder, with
+    //
+    // m' = a*q - m
+    // q' = (d - m'^2)/q
+    //
+    // where the division is always exact.
+    // The expansion of sqrt(d) is `[a0; (a1, a2, ..., a2, a1, 2*a0)]`, so the period ends at the first term of `2*a0`.
+    // If `d` is a perfect square then the expansion is just its root.
+    pub fn sqrt(d: u64) -> ContinuedFraction {
+        let a0 = isqrt(d);
+        let prefix = vec![a0];
+        if a0 * a0 == d {
+            return ContinuedFraction { prefix, period: Vec::new() };
+        }
+
+        let mut period = Vec::new();
+        let (mut m, mut q, mut a) = (0, 1, a0);
+        while a != 2 * a0 {
+            m = a * q - m;
+            q = (d - m * m) / q;
+            a = (a0 + m) / q;
+            period.push(a);
+        }
+        ContinuedFraction { prefix, period }
+    }
+
+    pub fn prefix(&self) -> &[u64] {
+        &self.prefix
+    }
+
+    pub fn period(&self) -> &[u64] {
+        &self.period
+    }
+
+    pub fn is_periodic(&self) -> bool {
+        !self.period.is_empty()
+    }
+
+    // The terms a0, a1, a2, ..., forever if this is periodic.
+    pub fn terms(&self) -> impl Iterator<Item = u64> + '_ {
+        let repeats = if self.period.is_empty() { 0 } else { usize::MAX };
+        self.prefix.iter().chain(self.period.iter().cycle().take(repeats)).copied()
+    }
+
+    // The convergents, the fractions from cutting off the expansion after each term.
+    pub fn convergents(&self) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        convergents(self.terms())
+    }
+
+    // The value of a finite expansion, or `None` if it is periodic.
+    pub fn to_rational(&self) -> Option<Rational> {
+        if self.is_periodic() {
+            return None;
+        }
+        self.convergents().last().map(|(p, q)| Rational::new(p, q))
+    }
+}
+
+// Printed as `[a0; a1, a2, (p1, p2, ...)]`, with the period in brackets.
+impl std::fmt::Display for ContinuedFraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |ts: &[u64]| ts.iter().map(u64::to_string).collect::<Vec<_>>().join(", ");
+
+        write!(f, "[{}", self.prefix.first().copied().unwrap_or(0))?;
+        let rest = self.prefix.get(1..).unwrap_or(&[]);
+        if rest.is_empty() && self.period.is_empty() {
+            return write!(f, "]");
+        }
+        write!(f, "; {}", join(rest))?;
+        if !self.period.is_empty() {
+            if !rest.is_empty() {
+                write!(f, ", ")?;
+            }
+            write!(f, "({})", join(&self.period))?;
+        }
+        write!(f, "]")
+    }
+}
+
+// The convergents `p_n/q_n` of the continued fraction with the given terms.
+//
+// These come from the recurrences
+//
+// p_n = a_n * p_(n-1) + p_(n-2)
+// q_n = a_n * q_(n-1) + q_(n-2)
+//
+// starting from p_(-1)/q_(-1) = 1/0 and p_(-2)/q_(-2) = 0/1.
+// They are always in lowest terms, and grow exponentially, so they are `Decimal`s.
+pub fn convergents<I: IntoIterator<Item = u64>>(terms: I) -> impl Iterator<Item = (Decimal, Decimal)> {
+    let mut p = (Decimal::zero(), Decimal::from(1u32));
+    let mut q = (Decimal::from(1u32), Decimal::zero());
+    terms.into_iter().map(move |a| {
+        let a = Decimal::from(a);
+        let p_n = &(&a * &p.1) + &p.0;
+        let q_n = &(&a * &q.1) + &q.0;
+        p.0 = std::mem::replace(&mut p.1, p_n.clone());
+        q.0 = std::mem::replace(&mut q.1, q_n.clone());
+        (p_n, q_n)
+    })
+}
+
+// The terms of the continued fraction for e, [2; 1, 2, 1, 1, 4, 1, 1, 6, ...].
+//
+// After the 2, the terms come in threes, 1, 2k, 1.
+pub fn e_terms() -> impl Iterator<Item = u64> {
+    std::iter::once(2).chain((1..).flat_map(|k| vec![1, 2 * k, 1]))
+}
+
+// Pell's equation, `x^2 - d*y^2 = 1`, and the negative Pell equation, `x^2 - d*y^2 = -1`, for a non-square `d`.
+//
+// The smallest positive solutions are convergents of sqrt(d), at the end of its period.
+// With a period of length `r`, `p_(r-1)/q_(r-1)` solves the equation when `r` is even, and the negative equation when `r` is odd.
+// In the odd case, `p_(2r-1)/q_(2r-1)`, at the end of the second time round the period, solves the positive one.
+// The negative equation has no solution when `r` is even.
+//
+// The rest of the solutions come from powers of the smallest: `x + y*sqrt(d) = (x1 + y1*sqrt(d))^n`.
+// Even powers of the negative solution solve the positive equation, and odd ones the negative equation.
+#[derive(Clone, Debug)]
+pub struct Pell {
+    d: Decimal,
+    fundamental: (Decimal, Decimal),
+    negative: Option<(Decimal, Decimal)>,
+}
+
+impl Pell {
+    // The equations for `d`, or `None` if `d` is a perfect square, when there are only trivial solutions.
+    pub fn new(d: u64) -> Option<Pell> {
+        let cf = ContinuedFraction::sqrt(d);
+        if !cf.is_periodic() {
+            return None;
+        }
+
+        let r = cf.period().len();
+        let mut convergents = cf.convergents();
+        let first = convergents.nth(r - 1).unwrap();
+        let (fundamental, negative) = if r % 2 == 0 {
+            (first, None)
+        } else {
+            (convergents.nth(r - 1).unwrap(), Some(first))
+        };
+
+        Some(Pell { d: Decimal::from(d), fundamental, negative })
+    }
+
+    // The smallest positive solution `(x, y)` of `x^2 - d*y^2 = 1`.
+    pub fn fundamental(&self) -> &(Decimal, Decimal) {
+        &self.fundamental
+    }
+
+    // The smallest positive solution `(x, y)` of `x^2 - d*y^2 = -1`, if there are any.
+    pub fn negative_fundamental(&self) -> Option<&(Decimal, Decimal)> {
+        self.negative.as_ref()
+    }
+
+    // All the positive solutions of `x^2 - d*y^2 = 1`, in increasing order.
+    pub fn solutions(&self) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        self.powers(self.fundamental.clone())
+    }
+
+    // All the positive solutions of `x^2 - d*y^2 = -1`, in increasing order.
+    //
+    // This is empty if there aren't any.
+    pub fn negative_solutions(&self) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        self.negative.iter().flat_map(move |n| self.powers(n.clone()))
+    }
+
+    // `start`, then `start` multiplied by the fundamental solution over and over.
+    fn powers(&self, start: (Decimal, Decimal)) -> impl Iterator<Item = (Decimal, Decimal)> + '_ {
+        let (x1, y1) = &self.fundamental;
+        std::iter::successors(Some(start), move |(x, y)| Some((
+            &(x1 * x) + &(&(&self.d * y1) * y),
+            &(x1 * y) + &(y1 * x),
+        )))
+    }
+}
@@ -1,135 +1,1293 @@
-use std::ops::{Add, Mul};
-use std::rc::{Rc};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
 
-#[derive(Debug)]
+use crate::numbers::{is_probable_prime_u128, nth_root_u128, MILLER_RABIN_WITNESSES};
+
+// Each limb holds 9 decimal digits, which is the most that fits in a u32.
+// The product of two limbs, plus a limb of carry, still fits comfortably in a u64.
+const LIMB_DIGITS: usize = 9;
+const BASE: u64 = 1_000_000_000;
+
+// Below this many limbs, schoolbook multiplication beats karatsuba's extra additions.
+const KARATSUBA_THRESHOLD: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Decimal {
-    // store the digits in reverse order, so that digits[0] is the units, digits[1] the 10s and so on.
-    digits: Vec<u8>,
+    // zero is never negative, so that there is exactly one representation of it
+    negative: bool,
+    // store the limbs in reverse order, so that limbs[0] is the units up to 10^9, limbs[1] the next 9 digits and so on.
+    // there are never any leading (most significant) zero limbs, so zero has no limbs at all.
+    limbs: Vec<u32>,
 }
 
 
 impl Decimal {
-    pub fn from_string(s: &str) -> Decimal {
-        Decimal {
-            digits: s.chars().rev().map(|c| c.to_digit(10).unwrap() as u8).collect()
+    pub fn from_u32(n: u32) -> Decimal {
+        Decimal::from(n)
+    }
+
+    // Build a decimal from a sign and a magnitude that fits in a machine word.
+    fn from_u128(negative: bool, n: u128) -> Decimal {
+        let base = u128::from(BASE);
+        let mut limbs = Vec::with_capacity(5);
+        let mut n = n;
+        while n != 0 {
+            limbs.push((n % base) as u32);
+            n /= base;
         }
+        Decimal::from_parts(negative, limbs)
     }
 
-    pub fn from_u32(n: u32) -> Decimal {
-        Decimal::from_string(&n.to_string())
+    // The magnitude as a machine word, if it fits.
+    fn to_u128(&self) -> Option<u128> {
+        self.limbs.iter().rev().try_fold(0u128, |acc, &l|
+            acc.checked_mul(u128::from(BASE))?.checked_add(u128::from(l)))
     }
 
     pub fn zero() -> Decimal {
         Decimal {
-            digits: Vec::new(),
+            negative: false,
+            limbs: Vec::new(),
+        }
+    }
+
+    // Build a decimal from a sign and big-endian base-10 digits.
+    fn from_digits(negative: bool, digits: &[u32]) -> Decimal {
+        // chunk the digits into limbs from the least significant end
+        let limbs = digits.rchunks(LIMB_DIGITS)
+            .map(|chunk| chunk.iter().fold(0, |l, &d| l * 10 + d))
+            .collect();
+
+        Decimal::from_parts(negative, limbs)
+    }
+
+    // Build a decimal from a sign and little-endian limbs, restoring the invariants.
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Decimal {
+        trim(&mut limbs);
+        let negative = negative && !limbs.is_empty();
+
+        Decimal { negative, limbs }
+    }
+
+    // The base-10 digits, least significant first.
+    //
+    // Zero has the single digit 0.
+    // These are unpacked from the limbs on each call, so hang on to the result rather than calling this in a loop.
+    pub fn digits(&self) -> Vec<u8> {
+        if self.is_zero() {
+            return vec![0];
+        }
+
+        let mut digits: Vec<u8> = Vec::with_capacity(self.limbs.len() * LIMB_DIGITS);
+        for &limb in &self.limbs {
+            let mut l = limb;
+            for _ in 0..LIMB_DIGITS {
+                digits.push((l % 10) as u8);
+                l /= 10;
+            }
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        digits
+    }
+
+    // The number formed by the `n` most significant digits, ignoring sign.
+    //
+    // If there are no more than `n` digits, this is just the absolute value.
+    pub fn leading_digits(&self, n: usize) -> Decimal {
+        let digits = self.digits();
+        if digits.len() <= n {
+            return self.abs();
+        }
+        let top: Vec<u32> = digits[digits.len() - n..].iter().rev().map(|&d| u32::from(d)).collect();
+        Decimal::from_digits(false, &top)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn is_positive(&self) -> bool {
+        !self.negative && !self.is_zero()
+    }
+
+    // The absolute value.
+    pub fn abs(&self) -> Decimal {
+        Decimal { negative: false, limbs: self.limbs.clone() }
+    }
+
+    // -1, 0 or 1 depending upon the sign.
+    pub fn signum(&self) -> i8 {
+        if self.negative { -1 } else if self.is_zero() { 0 } else { 1 }
+    }
+
+    // Quotient and remainder in one pass.
+    //
+    // This follows the machine integers: the quotient is truncated towards zero,
+    // and the remainder takes the sign of the dividend, so that `q * rhs + r == self`.
+    //
+    // Panics if `rhs` is zero.
+    pub fn div_rem(&self, rhs: &Decimal) -> (Decimal, Decimal) {
+        assert!(!rhs.is_zero(), "attempt to divide by zero");
+
+        let (q, r) = div_rem_magnitude(&self.limbs, &rhs.limbs);
+        (
+            Decimal::from_parts(self.negative != rhs.negative, q),
+            Decimal::from_parts(self.negative, r),
+        )
+    }
+
+    // Divide by a machine word, returning the quotient and the magnitude of the remainder.
+    //
+    // This is much cheaper than a full long division, as each step only needs a single machine division.
+    //
+    // Panics if `rhs` is zero.
+    pub fn div_rem_small(&self, rhs: u32) -> (Decimal, u32) {
+        assert!(rhs != 0, "attempt to divide by zero");
+
+        let (q, r) = div_rem_limb(&self.limbs, rhs);
+        (Decimal::from_parts(self.negative, q), r)
+    }
+
+    // Raise to a power by repeated squaring.
+    //
+    // Anything to the power 0 is 1, including 0.
+    pub fn pow(&self, exp: u32) -> Decimal {
+        let mut result = Decimal::from_u32(1);
+        let mut base = self.clone();
+        let mut e = exp;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = &result * &base;
+            }
+            e >>= 1;
+            if e > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    // Raise to a power, modulo `modulus`.
+    //
+    // The intermediate values are reduced after each multiplication, so they never grow much past the modulus.
+    // The result is always in `0 .. |modulus|`, even when `self` is negative.
+    //
+    // Panics if `modulus` is zero or `exp` is negative.
+    pub fn mod_pow(&self, exp: &Decimal, modulus: &Decimal) -> Decimal {
+        assert!(!exp.is_negative(), "negative exponent in mod_pow");
+        let modulus = modulus.abs();
+
+        let mut result = &Decimal::from_u32(1) % &modulus;
+        let mut base = self.rem_euclid(&modulus);
+        let mut e = exp.clone();
+        while !e.is_zero() {
+            let (half, bit) = e.div_rem_small(2);
+            if bit == 1 {
+                result = &(&result * &base) % &modulus;
+            }
+            e = half;
+            if !e.is_zero() {
+                base = &(&base * &base) % &modulus;
+            }
+        }
+        result
+    }
+
+    // The greatest common divisor, by Euclid's algorithm.
+    //
+    // This is always non-negative, and the gcd of zero and zero is zero.
+    pub fn gcd(&self, other: &Decimal) -> Decimal {
+        let mut a = self.abs();
+        let mut b = other.abs();
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    // Integer square root, the largest `r` with `r*r <= self`.
+    //
+    // Panics if negative.
+    pub fn isqrt(&self) -> Decimal {
+        self.nth_root(2)
+    }
+
+    // Integer cube root, truncated towards zero.
+    pub fn icbrt(&self) -> Decimal {
+        self.nth_root(3)
+    }
+
+    // Integer k'th root, truncated towards zero.
+    //
+    // Odd roots of negative numbers are negative.
+    //
+    // Panics if `k` is zero, or if `k` is even and `self` is negative.
+    pub fn nth_root(&self, k: u32) -> Decimal {
+        assert!(k != 0, "the zeroth root is undefined");
+        assert!(!self.negative || k % 2 == 1, "even root of a negative number");
+
+        let root = match self.to_u128() {
+            Some(n) => Decimal::from(nth_root_u128(n, k)),
+            None => self.abs().nth_root_newton(k),
+        };
+        if self.negative { -root } else { root }
+    }
+
+    // Newton's method, as for `numbers::nth_root_u128`, but starting from a power of ten above the root.
+    fn nth_root_newton(&self, k: u32) -> Decimal {
+        let k_d = Decimal::from(k);
+        let k_1 = Decimal::from(k - 1);
+        let digits = self.magnitude_string().len() as u32;
+        let mut x = Decimal::from(10u32).pow(digits.div_ceil(k));
+        loop {
+            let y = &(&(&k_1 * &x) + &(self / &x.pow(k - 1))) / &k_d;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn is_perfect_square(&self) -> bool {
+        !self.negative && &self.isqrt().pow(2) == self
+    }
+
+    // Is this `m^k` for some `m` and some `k >= 2`?
+    //
+    // 0 and 1 count, as they are their own squares.
+    // Negative numbers can only be odd powers.
+    pub fn is_perfect_power(&self) -> bool {
+        if self.abs() <= Decimal::from(1u32) && !self.negative {
+            return true;
         }
+        // an upper bound on the bit length, as log2(10) < 10/3
+        let bits = self.magnitude_string().len() as u32 * 10 / 3 + 1;
+        (2..=bits)
+            .filter(|&k| (2..k).all(|d| k % d != 0))
+            .filter(|&k| !self.negative || k % 2 == 1)
+            .any(|k| &self.nth_root(k).pow(k) == self)
     }
 
-    pub fn digits(&self) -> &[u8] {
-        &self.digits[..]
+    // Is this a strong probable prime to each of the bases in `numbers::MILLER_RABIN_WITNESSES`?
+    //
+    // Numbers that fit in a `u128` go through `numbers::is_probable_prime_u128`, which is exact below 3.3 * 10^24.
+    // Larger ones get the same Miller-Rabin rounds on `Decimal`s, so a composite could in principle pass.
+    // Negative numbers are never prime.
+    pub fn is_probable_prime(&self) -> bool {
+        if self.negative {
+            return false;
+        }
+        if let Some(n) = self.to_u128() {
+            return is_probable_prime_u128(n);
+        }
+        // the base is even, so the parity is that of the lowest limb
+        if self.limbs[0] % 2 == 0 {
+            return false;
+        }
+
+        let one = Decimal::from(1u32);
+        let n_1 = self - &one;
+        let mut d = n_1.clone();
+        let mut s = 0;
+        loop {
+            let (half, bit) = d.div_rem_small(2);
+            if bit == 1 {
+                break;
+            }
+            d = half;
+            s += 1;
+        }
+
+        MILLER_RABIN_WITNESSES.iter().all(|&a| {
+            let mut x = Decimal::from(a).mod_pow(&d, self);
+            if x == one || x == n_1 {
+                return true;
+            }
+            for _ in 1..s {
+                x = &(&x * &x) % self;
+                if x == n_1 {
+                    return true;
+                }
+            }
+            false
+        })
     }
 
-    pub fn fib() -> impl Iterator<Item = Rc<Decimal>> {
-        let mut f0 = Rc::new(Decimal::from_u32(1));
-        let mut f1 = f0.clone();
+    // The remainder, shifted so that it is never negative.
+    fn rem_euclid(&self, modulus: &Decimal) -> Decimal {
+        let r = self % modulus;
+        if r.is_negative() { &r + &modulus.abs() } else { r }
+    }
+
+    // Fibonacci numbers, indexed from 0 in the same way as `numbers::fib`, so this yields 0, 1, 1, 2, 3, 5, ...
+    pub fn fib() -> impl Iterator<Item = Decimal> {
+        let mut f0 = Decimal::zero();
+        let mut f1 = Decimal::from(1u32);
 
         std::iter::from_fn(move || {
-            let f = f0.clone();
-            f0 = f1.clone();
-            f1 = Rc::new(f.as_ref() + f0.as_ref());
-            
+            let f2 = &f0 + &f1;
+            let f = std::mem::replace(&mut f0, std::mem::replace(&mut f1, f2));
             Some(f)
         })
     }
+
+    // The n'th Fibonacci number by fast doubling, as for `numbers::nth_fib`.
+    pub fn nth_fib(n: u64) -> Decimal {
+        let two = Decimal::from(2u32);
+        let mut a = Decimal::zero();
+        let mut b = Decimal::from(1u32);
+        for bit in (0..64 - n.leading_zeros()).rev() {
+            let c = &a * &(&(&two * &b) - &a);
+            let d = &(&a * &a) + &(&b * &b);
+            if n >> bit & 1 == 0 {
+                a = c;
+                b = d;
+            } else {
+                b = &c + &d;
+                a = d;
+            }
+        }
+        a
+    }
 }
 
-impl std::fmt::Display for Decimal {
+// Drop any leading (most significant) zero limbs.
+fn trim(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+// Compare two magnitudes, ignoring sign.
+//
+// As there are no leading zeros, a longer number is always larger.
+// Numbers of the same length are compared from the most significant limb down.
+fn cmp_magnitude(lhs: &[u32], rhs: &[u32]) -> Ordering {
+    lhs.len().cmp(&rhs.len())
+        .then_with(|| lhs.iter().rev().cmp(rhs.iter().rev()))
+}
+
+fn add_magnitude(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    // max len of lhs, rhs
+    let l = lhs.len().max(rhs.len());
+
+    // reserve +1 limbs for sum overflow
+    let mut sum: Vec<u32> = Vec::with_capacity(l+1);
+
+    let mut carry = 0;
+    for i in 0..l {
+        let lhs_i = if i < lhs.len() { lhs[i] } else { 0 };
+        let rhs_i = if i < rhs.len() { rhs[i] } else { 0 };
+
+        let s = u64::from(lhs_i) + u64::from(rhs_i) + carry;
+        sum.push((s % BASE) as u32);
+        carry = s / BASE;
+    }
+
+    if carry != 0 {
+        sum.push(carry as u32);
+    }
+
+    sum
+}
+
+// Add `rhs` into `acc`, starting `offset` limbs up.
+//
+// `acc` must be long enough to hold the result.
+fn add_into(acc: &mut [u32], rhs: &[u32], offset: usize) {
+    let mut carry = 0;
+    let mut i = 0;
+    while i < rhs.len() || carry != 0 {
+        let rhs_i = if i < rhs.len() { rhs[i] } else { 0 };
+        let s = u64::from(acc[offset + i]) + u64::from(rhs_i) + carry;
+        acc[offset + i] = (s % BASE) as u32;
+        carry = s / BASE;
+        i += 1;
+    }
+}
+
+// Subtract the magnitude `rhs` from `lhs`, which must be at least as large.
+//
+// The result may have leading zeros, which the caller is responsible for removing.
+fn sub_magnitude(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut diff = lhs.to_vec();
+    sub_into(&mut diff, rhs);
+    diff
+}
+
+// Subtract `rhs` from `acc` in place, where `acc` must be at least as large.
+fn sub_into(acc: &mut [u32], rhs: &[u32]) {
+    let mut borrow = 0;
+    let mut i = 0;
+    while i < rhs.len() || borrow != 0 {
+        let take = u64::from(if i < rhs.len() { rhs[i] } else { 0 }) + borrow;
+        let acc_i = u64::from(acc[i]);
+        if acc_i >= take {
+            acc[i] = (acc_i - take) as u32;
+            borrow = 0;
+        } else {
+            acc[i] = (acc_i + BASE - take) as u32;
+            borrow = 1;
+        }
+        i += 1;
+    }
+}
+
+// Multiply two magnitudes.
+//
+// Small operands use the schoolbook method, and larger ones are split recursively with karatsuba.
+// The result may have leading zeros.
+fn mul_magnitude(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    if lhs.is_empty() || rhs.is_empty() {
+        return Vec::new();
+    }
+
+    if lhs.len().min(rhs.len()) < KARATSUBA_THRESHOLD {
+        schoolbook(lhs, rhs)
+    } else {
+        karatsuba(lhs, rhs)
+    }
+}
+
+// Long multiplication, one row per limb of `lhs`.
+//
+// Each limb product is below 10^18, so a row can accumulate into a u64 with its carry without overflowing.
+fn schoolbook(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; lhs.len() + rhs.len()];
+
+    for (i, &l) in lhs.iter().enumerate() {
+        if l == 0 { continue; }
+        let mut carry = 0u64;
+        for (j, &r) in rhs.iter().enumerate() {
+            let cell = u64::from(product[i + j]) + u64::from(l) * u64::from(r) + carry;
+            product[i + j] = (cell % BASE) as u32;
+            carry = cell / BASE;
+        }
+        product[i + rhs.len()] = carry as u32;
+    }
+
+    product
+}
+
+// Karatsuba multiplication.
+//
+// Splitting each side at `m` limbs gives `lhs = l1*B^m + l0` and `rhs = r1*B^m + r0`, so that
+//  lhs * rhs = z2*B^2m + z1*B^m + z0
+// where `z2 = l1*r1`, `z0 = l0*r0`, and
+//  z1 = l1*r0 + l0*r1 = (l1 + l0)*(r1 + r0) - z2 - z0
+// which needs three half-sized products rather than four.
+fn karatsuba(lhs: &[u32], rhs: &[u32]) -> Vec<u32> {
+    let m = lhs.len().max(rhs.len()) / 2;
+
+    // very lopsided operands don't split usefully, so slice the long one up against the short one instead
+    let (short, long) = if lhs.len() < rhs.len() { (lhs, rhs) } else { (rhs, lhs) };
+    if short.len() <= m {
+        let mut product = vec![0u32; lhs.len() + rhs.len() + 1];
+        for (i, chunk) in long.chunks(short.len()).enumerate() {
+            let mut part = mul_magnitude(short, chunk);
+            trim(&mut part);
+            add_into(&mut product, &part, i * short.len());
+        }
+        return product;
+    }
+
+    let (l0, l1) = lhs.split_at(m);
+    let (r0, r1) = rhs.split_at(m);
+    let (l0, r0) = (trimmed(l0), trimmed(r0));
+
+    let mut z0 = mul_magnitude(l0, r0);
+    let mut z2 = mul_magnitude(l1, r1);
+    trim(&mut z0);
+    trim(&mut z2);
+
+    let mut z1 = mul_magnitude(&add_magnitude(l0, l1), &add_magnitude(r0, r1));
+    sub_into(&mut z1, &z0);
+    sub_into(&mut z1, &z2);
+    trim(&mut z1);
+
+    let mut product = vec![0u32; lhs.len() + rhs.len() + 1];
+    add_into(&mut product, &z0, 0);
+    add_into(&mut product, &z1, m);
+    add_into(&mut product, &z2, 2 * m);
+    product
+}
+
+// The low half of a split may have zeros at its top, which the magnitude helpers don't expect.
+fn trimmed(limbs: &[u32]) -> &[u32] {
+    let len = limbs.iter().rposition(|&l| l != 0).map_or(0, |i| i + 1);
+    &limbs[..len]
+}
+
+// Divide a magnitude by a single machine word, returning the quotient and remainder.
+//
+// The running remainder is always below `rhs`, so `remainder * BASE + limb` fits in a u64.
+fn div_rem_limb(lhs: &[u32], rhs: u32) -> (Vec<u32>, u32) {
+    let rhs = u64::from(rhs);
+    let mut q = vec![0u32; lhs.len()];
+    let mut r = 0u64;
+    for (i, &l) in lhs.iter().enumerate().rev() {
+        let acc = r * BASE + u64::from(l);
+        q[i] = (acc / rhs) as u32;
+        r = acc % rhs;
+    }
+    (q, r as u32)
+}
+
+// Multiply a magnitude by a single machine word.
+fn mul_limb(lhs: &[u32], rhs: u32) -> Vec<u32> {
+    mul_add_limb(lhs, rhs, 0)
+}
+
+// Multiply a magnitude by a single machine word, and add another.
+//
+// A machine word may be larger than the base, so the final carry can spill over into two limbs.
+fn mul_add_limb(lhs: &[u32], rhs: u32, add: u32) -> Vec<u32> {
+    let mut product = Vec::with_capacity(lhs.len() + 2);
+    let mut carry = u64::from(add);
+    for &l in lhs {
+        let cell = u64::from(l) * u64::from(rhs) + carry;
+        product.push((cell % BASE) as u32);
+        carry = cell / BASE;
+    }
+    while carry != 0 {
+        product.push((carry % BASE) as u32);
+        carry /= BASE;
+    }
+    product
+}
+
+// Long division of magnitudes, returning the quotient and remainder.
+//
+// This is Knuth's algorithm D, which is the pen-and-paper method with each quotient limb estimated from the top two limbs of the running remainder.
+// Both sides are first scaled so that the divisor's top limb is at least half the base.
+// That guarantees the estimate is at most two too large, and the corrections are cheap.
+fn div_rem_magnitude(lhs: &[u32], rhs: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(lhs, rhs) == Ordering::Less {
+        return (Vec::new(), lhs.to_vec());
+    }
+    if rhs.len() == 1 {
+        let (q, r) = div_rem_limb(lhs, rhs[0]);
+        return (q, vec![r]);
+    }
+
+    let n = rhs.len();
+    let scale = (BASE / (u64::from(rhs[n - 1]) + 1)) as u32;
+    let v = {
+        let mut v = mul_limb(rhs, scale);
+        v.truncate(n);
+        v
+    };
+    let mut u = mul_limb(lhs, scale);
+    if u.len() == lhs.len() {
+        u.push(0);
+    }
+
+    let v_top = u64::from(v[n - 1]);
+    let v_next = u64::from(v[n - 2]);
+    let m = u.len() - n - 1;
+    let mut q = vec![0u32; m + 1];
+
+    for j in (0..=m).rev() {
+        // estimate the quotient limb from the top of the running remainder
+        let top = u64::from(u[j + n]) * BASE + u64::from(u[j + n - 1]);
+        let mut q_hat = top / v_top;
+        let mut r_hat = top % v_top;
+        while q_hat >= BASE || q_hat * v_next > r_hat * BASE + u64::from(u[j + n - 2]) {
+            q_hat -= 1;
+            r_hat += v_top;
+            if r_hat >= BASE { break; }
+        }
+
+        // u[j ..= j+n] -= q_hat * v
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = q_hat * u64::from(v[i]) + carry;
+            carry = p / BASE;
+            let t = i64::from(u[i + j]) - (p % BASE) as i64 + borrow;
+            u[i + j] = t.rem_euclid(BASE as i64) as u32;
+            borrow = t.div_euclid(BASE as i64);
+        }
+        let t = i64::from(u[j + n]) - carry as i64 + borrow;
+        u[j + n] = t.rem_euclid(BASE as i64) as u32;
+
+        // the estimate was still one too many, so add the divisor back
+        if t < 0 {
+            q_hat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let s = u64::from(u[i + j]) + u64::from(v[i]) + carry;
+                u[i + j] = (s % BASE) as u32;
+                carry = s / BASE;
+            }
+            u[j + n] = ((u64::from(u[j + n]) + carry) % BASE) as u32;
+        }
+
+        q[j] = q_hat as u32;
+    }
+
+    // undo the scaling on the remainder
+    let (r, _) = div_rem_limb(&u[..n], scale);
+    (q, r)
+}
+
+// Sum two signed values.
+//
+// When the signs agree, the magnitudes add and the sign is kept.
+// When they differ, the smaller magnitude is taken from the larger, and the result has the sign of the larger.
+fn add_signed(l_neg: bool, lhs: &[u32], r_neg: bool, rhs: &[u32]) -> Decimal {
+    if l_neg == r_neg {
+        Decimal::from_parts(l_neg, add_magnitude(lhs, rhs))
+    } else {
+        match cmp_magnitude(lhs, rhs) {
+            Ordering::Less => Decimal::from_parts(r_neg, sub_magnitude(rhs, lhs)),
+            _ => Decimal::from_parts(l_neg, sub_magnitude(lhs, rhs)),
+        }
+    }
+}
+
+// Why a string could not be parsed as a `Decimal`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    // There were no digits at all, after removing the whitespace and sign.
+    Empty,
+    // A character that is not a digit, at this character offset into the original string.
+    InvalidDigit { position: usize, found: char },
+}
+
+impl std::fmt::Display for ParseDecimalError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.digits.iter().rev().map(|d| d.to_string()).collect::<String>())
+        match self {
+            ParseDecimalError::Empty =>
+                write!(f, "cannot parse a decimal from a string without digits"),
+            ParseDecimalError::InvalidDigit { position, found } =>
+                write!(f, "invalid digit {:?} at position {}", found, position),
+        }
     }
 }
 
-#[allow(clippy::suspicious_arithmetic_impl)]
-impl Mul for &Decimal {
-    type Output = Decimal;
+impl std::error::Error for ParseDecimalError {}
 
-    fn mul(self, rhs: &Decimal) -> Decimal {
-        // product length is the product of the individual lengths
-        let l = self.digits.len() + rhs.digits.len() - 1;
+impl std::str::FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    // Parse an optionally signed run of digits.
+    //
+    // The digits are decimal, unless they start with one of the prefixes `0x`, `0o` or `0b` for hexadecimal, octal or binary.
+    // Surrounding whitespace is ignored.
+    // Underscores may be used to group digits, e.g. `1_000_000`, but not before the first digit.
+    // Leading zeros are accepted, and are not significant.
+    fn from_str(s: &str) -> Result<Decimal, ParseDecimalError> {
+        parse(s, None)
+    }
+}
+
+// The shared parser behind `from_str` and `from_str_radix`.
+//
+// With no radix given, it is picked from the prefix, defaulting to decimal.
+fn parse(s: &str, radix: Option<u32>) -> Result<Decimal, ParseDecimalError> {
+    let leading = s.chars().take_while(|c| c.is_whitespace()).count();
+    let chars: Vec<(usize, char)> = s.trim().chars().enumerate()
+        .map(|(i, c)| (i + leading, c))
+        .collect();
+    let mut chars = &chars[..];
+
+    let negative = match chars.first() {
+        Some((_, '-')) => { chars = &chars[1..]; true }
+        Some((_, '+')) => { chars = &chars[1..]; false }
+        _ => false,
+    };
+
+    let radix = match radix {
+        Some(radix) => radix,
+        None => {
+            let prefixed = match chars {
+                [(_, '0'), (_, 'x'), ..] | [(_, '0'), (_, 'X'), ..] => Some(16),
+                [(_, '0'), (_, 'o'), ..] | [(_, '0'), (_, 'O'), ..] => Some(8),
+                [(_, '0'), (_, 'b'), ..] | [(_, '0'), (_, 'B'), ..] => Some(2),
+                _ => None,
+            };
+            if prefixed.is_some() {
+                chars = &chars[2..];
+            }
+            prefixed.unwrap_or(10)
+        }
+    };
+
+    let mut digits: Vec<u8> = Vec::with_capacity(chars.len());
+    for &(position, c) in chars {
+        match c.to_digit(radix) {
+            Some(d) => digits.push(d as u8),
+            None if c == '_' && !digits.is_empty() => (),
+            None => return Err(ParseDecimalError::InvalidDigit { position, found: c }),
+        }
+    }
+
+    if digits.is_empty() {
+        return Err(ParseDecimalError::Empty);
+    }
+
+    let magnitude = if radix == 10 {
+        Decimal::from_digits(false, &digits.iter().map(|&d| u32::from(d)).collect::<Vec<_>>())
+    } else {
+        digits.reverse();
+        radix_to_decimal(&digits, radix)
+    };
+    Ok(if negative { -magnitude } else { magnitude })
+}
 
-        // we should make a rectangular table, but it simplifies things later if it is square and padded with zeros
-        let mut table = vec![vec![0u8; rhs.digits.len()]; self.digits.len()];
+fn check_radix(radix: u32) {
+    assert!((2..=36).contains(&radix), "radix must be in 2..=36, but was {}", radix);
+}
+
+// The largest power of `radix` that fits in a u32, and the exponent that gives it.
+//
+// Conversions work a whole chunk of digits at a time, so that each step is a single machine-word multiply or divide.
+fn radix_chunk(radix: u32) -> (u32, usize) {
+    let mut power = radix;
+    let mut count = 1;
+    while let Some(p) = power.checked_mul(radix) {
+        power = p;
+        count += 1;
+    }
+    (power, count)
+}
+
+// Horner's method over little-endian digits that are already known to be in range.
+fn radix_to_decimal(digits: &[u8], radix: u32) -> Decimal {
+    let (_, count) = radix_chunk(radix);
+    let mut limbs: Vec<u32> = Vec::new();
+    for chunk in digits.rchunks(count) {
+        // the most significant chunk may be short, so the shift is worked out for each one
+        let scale = radix.pow(chunk.len() as u32);
+        let value = chunk.iter().rev().fold(0, |l, &d| l * radix + u32::from(d));
+        limbs = mul_add_limb(&limbs, scale, value);
+    }
+    Decimal::from_parts(false, limbs)
+}
+
+impl Decimal {
+    // The digits of the magnitude in the given radix, least significant first.
+    //
+    // Zero has the single digit 0.
+    //
+    // Panics if `radix` is not in `2..=36`.
+    pub fn to_radix(&self, radix: u32) -> Vec<u8> {
+        check_radix(radix);
+        if self.is_zero() {
+            return vec![0];
+        }
+
+        let (power, count) = radix_chunk(radix);
+        let mut digits: Vec<u8> = Vec::new();
+        let mut rest = self.limbs.clone();
+        while !rest.is_empty() {
+            let (q, mut r) = div_rem_limb(&rest, power);
+            rest = q;
+            trim(&mut rest);
+            for _ in 0..count {
+                digits.push((r % radix) as u8);
+                r /= radix;
+            }
+        }
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        digits
+    }
+
+    // Build a non-negative value from its digits in the given radix, least significant first.
+    //
+    // Returns `None` if any digit is not less than the radix.
+    //
+    // Panics if `radix` is not in `2..=36`.
+    pub fn from_radix(digits: &[u8], radix: u32) -> Option<Decimal> {
+        check_radix(radix);
+        if digits.iter().any(|&d| u32::from(d) >= radix) {
+            return None;
+        }
+        Some(radix_to_decimal(digits, radix))
+    }
+
+    // Lazily iterate over the digits of the magnitude in the given radix, least significant first.
+    //
+    // Each step is a division by the radix, so this is worthwhile when only the lowest few digits are needed.
+    // To get all of them, `to_radix` is faster.
+    //
+    // Panics if `radix` is not in `2..=36`.
+    pub fn radix_digits(&self, radix: u32) -> impl Iterator<Item = u8> {
+        check_radix(radix);
+        let mut rest = self.abs();
+        let mut first = true;
+
+        std::iter::from_fn(move || {
+            if rest.is_zero() && !first {
+                return None;
+            }
+            first = false;
+            let (q, r) = rest.div_rem_small(radix);
+            rest = q;
+            Some(r as u8)
+        })
+    }
+
+    // Format in the given radix, using lowercase letters for digits past 9.
+    //
+    // Panics if `radix` is not in `2..=36`.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits: String = self.to_radix(radix).iter().rev()
+            .map(|&d| std::char::from_digit(u32::from(d), radix).unwrap())
+            .collect();
+        if self.negative { format!("-{}", digits) } else { digits }
+    }
+
+    // Parse digits in the given radix, which may be in either case.
+    //
+    // This accepts the same sign, whitespace and underscores as `from_str`, but not a radix prefix.
+    //
+    // Panics if `radix` is not in `2..=36`.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Decimal, ParseDecimalError> {
+        check_radix(radix);
+        parse(s, Some(radix))
+    }
+}
+
+// Conversions from the machine integers can never fail.
+macro_rules! from_unsigned {
+    ($($t:ty),*) => { $(
+        impl From<$t> for Decimal {
+            fn from(n: $t) -> Decimal {
+                Decimal::from_u128(false, n as u128)
+            }
+        }
+    )* };
+}
 
-        // for i in 0 .. self.digits.len() {
-        //     for j in 0 .. rhs.digits.len() {
-        //         table[i][j] = self.digits[i]*rhs.digits[j];
-        //     }
-        // }
-        for (i, row) in table.iter_mut().enumerate() {
-            let d_i = self.digits[i];
-            for (j, cell) in row.iter_mut().enumerate() {
-                *cell = d_i*rhs.digits[j];
+macro_rules! from_signed {
+    ($($t:ty),*) => { $(
+        impl From<$t> for Decimal {
+            fn from(n: $t) -> Decimal {
+                Decimal::from_u128(n < 0, n.unsigned_abs() as u128)
             }
         }
+    )* };
+}
 
+from_unsigned!(u8, u16, u32, u64, u128, usize);
+from_signed!(i8, i16, i32, i64, i128, isize);
 
-        // println!("table: {:?}", table);
+// The value of a `Decimal` did not fit in the machine integer it was being converted to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromDecimalError(());
 
-        let mut diags: Vec<u16> = Vec::with_capacity(l+1);
-        for i in 0 .. l {
-            // println!("i: {}", i);
-            let mut d_i = 0u16;
-            for j in 0 ..= i {
-                let k = i - j;
-                if k < self.digits.len() && j < rhs.digits.len() {
-                    // println!("\t{} x {}", k, j);
-                    d_i += u16::from(table[i-j][j]);
+impl std::fmt::Display for TryFromDecimalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "decimal value out of range for the target integer type")
+    }
+}
+
+impl std::error::Error for TryFromDecimalError {}
+
+// Conversions to the machine integers fail if the value is out of range, including negative values for the unsigned types.
+macro_rules! try_into_unsigned {
+    ($($t:ty),*) => { $(
+        impl TryFrom<&Decimal> for $t {
+            type Error = TryFromDecimalError;
+
+            fn try_from(d: &Decimal) -> Result<$t, TryFromDecimalError> {
+                if d.negative {
+                    return Err(TryFromDecimalError(()));
                 }
+                d.to_u128()
+                    .and_then(|n| <$t>::try_from(n).ok())
+                    .ok_or(TryFromDecimalError(()))
             }
-            diags.push(d_i);
         }
+    )* };
+}
 
-        let mut carry = 0u16;
-        let mut digits: Vec<u8> = Vec::new();
-        for d in diags {
-            let s = d + carry;
-            digits.push((s % 10) as u8);
-            carry = s / 10;
+macro_rules! try_into_signed {
+    ($($t:ty),*) => { $(
+        impl TryFrom<&Decimal> for $t {
+            type Error = TryFromDecimalError;
+
+            fn try_from(d: &Decimal) -> Result<$t, TryFromDecimalError> {
+                // a negative magnitude can reach one further than a positive one, so work through i128 with wrapping negation
+                let n = d.to_u128()
+                    .filter(|&n| n <= i128::MIN.unsigned_abs())
+                    .map(|n| if d.negative { (n as i128).wrapping_neg() } else { n as i128 })
+                    .filter(|&n| d.negative || n >= 0);
+                n.and_then(|n| <$t>::try_from(n).ok())
+                    .ok_or(TryFromDecimalError(()))
+            }
+        }
+    )* };
+}
+
+try_into_unsigned!(u8, u16, u32, u64, u128, usize);
+try_into_signed!(i8, i16, i32, i64, i128, isize);
+
+impl std::fmt::Display for Decimal {
+    // The plain digits, with the usual width, fill and sign flags.
+    //
+    // The alternate flag groups the digits in thousands, so `{:#}` prints `1,234,567`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.magnitude_string();
+        let digits = if f.alternate() { group_thousands(&digits) } else { digits };
+        f.pad_integral(!self.negative, "", &digits)
+    }
+}
+
+// Scientific notation, e.g. `{:e}` prints 1234500 as `1.2345e6`.
+//
+// Without a precision, all the significant digits are shown.
+// With one, e.g. `{:.9e}`, exactly that many digits follow the point.
+// The digits are truncated rather than rounded, so that `{:.9e}` always starts with the true first ten digits.
+impl std::fmt::LowerExp for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self.scientific(f.precision(), 'e');
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+impl std::fmt::UpperExp for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self.scientific(f.precision(), 'E');
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+// Formatting in the other common bases.
+//
+// The alternate flag adds the usual prefix, so `{:#x}` prints 255 as `0xff`.
+macro_rules! radix_fmt {
+    ($tr:ident, $radix:expr, $prefix:expr, $upper:expr) => {
+        impl std::fmt::$tr for Decimal {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let digits = self.abs().to_str_radix($radix);
+                let digits = if $upper { digits.to_uppercase() } else { digits };
+                f.pad_integral(!self.negative, $prefix, &digits)
+            }
+        }
+    };
+}
+
+radix_fmt!(Binary, 2, "0b", false);
+radix_fmt!(Octal, 8, "0o", false);
+radix_fmt!(LowerHex, 16, "0x", false);
+radix_fmt!(UpperHex, 16, "0x", true);
+
+impl Decimal {
+    // The digits of the magnitude, most significant first.
+    fn magnitude_string(&self) -> String {
+        // the top limb is printed as-is, but the rest need their leading zeros
+        let mut limbs = self.limbs.iter().rev();
+        let mut s = match limbs.next() {
+            Some(top) => top.to_string(),
+            None => return "0".to_string(),
+        };
+        for limb in limbs {
+            s.push_str(&format!("{:09}", limb));
+        }
+        s
+    }
+
+    // The unsigned mantissa and exponent.
+    fn scientific(&self, precision: Option<usize>, e: char) -> String {
+        let digits = self.magnitude_string();
+        let exponent = digits.len() - 1;
+        let (first, rest) = digits.split_at(1);
+
+        let fraction = match precision {
+            Some(p) if p <= rest.len() => rest[..p].to_string(),
+            Some(p) => format!("{:0<width$}", rest, width = p),
+            None => rest.trim_end_matches('0').to_string(),
+        };
+
+        if fraction.is_empty() {
+            format!("{}{}{}", first, e, exponent)
+        } else {
+            format!("{}.{}{}{}", first, fraction, e, exponent)
+        }
+    }
+}
+
+// Insert a comma between each group of three digits, counting from the right.
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
         }
-        if carry != 0 { digits.push(carry as u8); }
+        grouped.push(c);
+    }
+    grouped
+}
 
-        Decimal { digits }
+impl std::iter::Sum for Decimal {
+    fn sum<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::zero(), |l, r| &l + &r)
     }
 }
 
+impl<'a> std::iter::Sum<&'a Decimal> for Decimal {
+    fn sum<I: Iterator<Item = &'a Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::zero(), |l, r| &l + r)
+    }
+}
+
+impl std::iter::Product for Decimal {
+    fn product<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::from_u32(1), |l, r| &l * &r)
+    }
+}
+
+impl<'a> std::iter::Product<&'a Decimal> for Decimal {
+    fn product<I: Iterator<Item = &'a Decimal>>(iter: I) -> Decimal {
+        iter.fold(Decimal::from_u32(1), |l, r| &l * r)
+    }
+}
+
+impl Mul for &Decimal {
+    type Output = Decimal;
+
+    fn mul(self, rhs: &Decimal) -> Decimal {
+        // the sign of a product is negative only if exactly one side is
+        Decimal::from_parts(self.negative != rhs.negative, mul_magnitude(&self.limbs, &rhs.limbs))
+    }
+}
 
-#[allow(clippy::suspicious_arithmetic_impl)]
 impl Add for &Decimal {
     type Output = Decimal;
 
     fn add(self, rhs: &Decimal) -> Decimal {
-        // max len of lhs, rhs
-        let l = self.digits.len().max(rhs.digits.len());
+        add_signed(self.negative, &self.limbs, rhs.negative, &rhs.limbs)
+    }
+}
+
+impl Sub for &Decimal {
+    type Output = Decimal;
+
+    // Subtraction is addition of the negated right hand side.
+    fn sub(self, rhs: &Decimal) -> Decimal {
+        add_signed(self.negative, &self.limbs, !rhs.negative, &rhs.limbs)
+    }
+}
+
+impl Div for &Decimal {
+    type Output = Decimal;
+
+    fn div(self, rhs: &Decimal) -> Decimal {
+        self.div_rem(rhs).0
+    }
+}
+
+impl Rem for &Decimal {
+    type Output = Decimal;
+
+    fn rem(self, rhs: &Decimal) -> Decimal {
+        self.div_rem(rhs).1
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::from_parts(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    fn neg(self) -> Decimal {
+        Decimal::from_parts(!self.negative, self.limbs)
+    }
+}
+
+// The arithmetic is all implemented on references.
+// These forward the owned and mixed variants, and the assigning operators, onto those.
+macro_rules! forward_binop {
+    ($t:ty, $tr:ident, $m:ident, $tr_assign:ident, $m_assign:ident) => {
+        impl $tr<$t> for $t {
+            type Output = $t;
+
+            fn $m(self, rhs: $t) -> $t { (&self).$m(&rhs) }
+        }
+
+        impl $tr<&$t> for $t {
+            type Output = $t;
+
+            fn $m(self, rhs: &$t) -> $t { (&self).$m(rhs) }
+        }
+
+        impl $tr<$t> for &$t {
+            type Output = $t;
+
+            fn $m(self, rhs: $t) -> $t { self.$m(&rhs) }
+        }
+
+        impl $tr_assign<&$t> for $t {
+            fn $m_assign(&mut self, rhs: &$t) { *self = (&*self).$m(rhs) }
+        }
+
+        impl $tr_assign<$t> for $t {
+            fn $m_assign(&mut self, rhs: $t) { *self = (&*self).$m(&rhs) }
+        }
+    };
+}
+
+pub(crate) use forward_binop;
+
+forward_binop!(Decimal, Add, add, AddAssign, add_assign);
+forward_binop!(Decimal, Sub, sub, SubAssign, sub_assign);
+forward_binop!(Decimal, Mul, mul, MulAssign, mul_assign);
+forward_binop!(Decimal, Div, div, DivAssign, div_assign);
+forward_binop!(Decimal, Rem, rem, RemAssign, rem_assign);
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    // Negative numbers sort before positive ones.
+    // Between two negative numbers, the one with the larger magnitude is smaller.
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => cmp_magnitude(&other.limbs, &self.limbs),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+// The `num-traits` numeric traits, so that `Decimal` can be used by code that is generic over integer types,
+// such as `numbers::Natural`.
+//
+// A `Decimal` never overflows, so the checked operations only fail when dividing by zero.
+impl num_traits::Zero for Decimal {
+    fn zero() -> Decimal {
+        Decimal::zero()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+}
+
+impl num_traits::One for Decimal {
+    fn one() -> Decimal {
+        Decimal::from_u32(1)
+    }
+}
+
+impl num_traits::Num for Decimal {
+    type FromStrRadixErr = ParseDecimalError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Decimal, ParseDecimalError> {
+        Decimal::from_str_radix(s, radix)
+    }
+}
+
+impl num_traits::CheckedAdd for Decimal {
+    fn checked_add(&self, v: &Decimal) -> Option<Decimal> {
+        Some(self + v)
+    }
+}
+
+impl num_traits::CheckedSub for Decimal {
+    fn checked_sub(&self, v: &Decimal) -> Option<Decimal> {
+        Some(self - v)
+    }
+}
+
+impl num_traits::CheckedMul for Decimal {
+    fn checked_mul(&self, v: &Decimal) -> Option<Decimal> {
+        Some(self * v)
+    }
+}
+
+impl num_traits::CheckedDiv for Decimal {
+    fn checked_div(&self, v: &Decimal) -> Option<Decimal> {
+        if v.is_zero() { None } else { Some(self / v) }
+    }
+}
+
+// Lossless conversions to and from the big integers in the `num` crate.
+//
+// These are only available with the `num` feature enabled.
+#[cfg(feature = "num")]
+mod num_conversions {
+    use std::convert::TryFrom;
 
-        // reserve +1 digits for sum overflow
-        let mut sum: Vec<u8> = Vec::with_capacity(l+1);
-        
-        let mut carry = 0;
-        for i in 0..l {
-            let self_i = if i < self.digits.len() { self.digits[i] } else { 0 };
-            let rhs_i = if i < rhs.digits.len()  { rhs.digits[i] } else { 0 };
+    use super::{BASE, Decimal, TryFromDecimalError, mul_add_limb};
+    use num::bigint::{BigInt, BigUint, Sign};
 
-            let s = self_i + rhs_i + carry;
-            let d = s % 10;
-            carry = s / 10;
+    impl From<&BigUint> for Decimal {
+        // Horner's method over the 32-bit digits, most significant first.
+        fn from(n: &BigUint) -> Decimal {
+            let limbs = n.iter_u32_digits().rev().fold(Vec::new(), |acc, d| {
+                // 2^32 doesn't fit in a u32, so shift up by it in two halves
+                let acc = mul_add_limb(&acc, 1 << 16, 0);
+                mul_add_limb(&acc, 1 << 16, d)
+            });
+            Decimal::from_parts(false, limbs)
+        }
+    }
 
-            sum.push(d);
+    impl From<BigUint> for Decimal {
+        fn from(n: BigUint) -> Decimal {
+            Decimal::from(&n)
         }
+    }
 
-        if carry != 0 {
-            sum.push(carry);
+    impl From<&BigInt> for Decimal {
+        fn from(n: &BigInt) -> Decimal {
+            let magnitude = Decimal::from(n.magnitude());
+            if n.sign() == Sign::Minus { -magnitude } else { magnitude }
         }
+    }
+
+    impl From<BigInt> for Decimal {
+        fn from(n: BigInt) -> Decimal {
+            Decimal::from(&n)
+        }
+    }
+
+    impl TryFrom<&Decimal> for BigUint {
+        type Error = TryFromDecimalError;
+
+        // Horner's method over the limbs, most significant first.
+        fn try_from(d: &Decimal) -> Result<BigUint, TryFromDecimalError> {
+            if d.negative {
+                return Err(TryFromDecimalError(()));
+            }
+            Ok(magnitude(d))
+        }
+    }
+
+    impl From<&Decimal> for BigInt {
+        fn from(d: &Decimal) -> BigInt {
+            let sign = if d.negative { Sign::Minus } else { Sign::Plus };
+            BigInt::from_biguint(sign, magnitude(d))
+        }
+    }
 
-        Decimal { digits: sum }
+    fn magnitude(d: &Decimal) -> BigUint {
+        d.limbs.iter().rev()
+            .fold(BigUint::default(), |acc, &l| acc * BASE + l)
     }
 }
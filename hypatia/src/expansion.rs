@@ -0,0 +1,189 @@
+use std::convert::TryFrom;
+
+use crate::decimal::Decimal;
+
+// The decimal expansion of a fraction `p/q`.
+//
+// Every fraction has a decimal expansion that either terminates or eventually repeats forever.
+// It is split into the integer part, the digits after the point that don't repeat (the prefix),
+// and then the digits that repeat (the repetend), so that e.g.
+//  1/6 = 0.1666... = 0.1(6)
+// has integer part 0, prefix [1] and repetend [6].
+// A terminating expansion has an empty repetend.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecimalExpansion {
+    negative: bool,
+    integer: Decimal,
+    prefix: Vec<u8>,
+    repetend: Vec<u8>,
+}
+
+impl DecimalExpansion {
+    // Expand `p/q` by long division.
+    //
+    // Once the fraction is in lowest terms, the length of the prefix is the larger of the powers of 2 and 5 in `q`,
+    // since those are the factors that 10 shares with it.
+    // After the prefix, the remainders are purely periodic, so the repetend ends when the remainder comes back to where it started.
+    //
+    // The repetend of `1/q` can be up to `q-1` digits long, so this is only practical for moderately sized denominators.
+    //
+    // Panics if `q` is zero.
+    pub fn new(p: &Decimal, q: &Decimal) -> DecimalExpansion {
+        assert!(!q.is_zero(), "attempt to divide by zero");
+
+        let negative = p.is_negative() != q.is_negative() && !p.is_zero();
+        let g = p.gcd(q);
+        let p = &p.abs() / &g;
+        let q = &q.abs() / &g;
+
+        let (integer, mut r) = p.div_rem(&q);
+
+        // `Primes::factorise` only takes a `u64`, and only the 2s and 5s are needed, so divide them out of the `Decimal` directly
+        let (twos, rest) = strip_factor(&q, 2);
+        let (fives, rest) = strip_factor(&rest, 5);
+        let prefix_len = twos.max(fives);
+
+        let next_digit = |r: &mut Decimal| {
+            let (d, r_next) = (&*r * &Decimal::from(10u32)).div_rem(&q);
+            *r = r_next;
+            u8::try_from(&d).unwrap()
+        };
+
+        let prefix: Vec<u8> = (0..prefix_len).map(|_| next_digit(&mut r)).collect();
+
+        let mut repetend = Vec::new();
+        if rest != Decimal::from(1u32) {
+            let r0 = r.clone();
+            loop {
+                repetend.push(next_digit(&mut r));
+                if r == r0 { break; }
+            }
+        }
+
+        DecimalExpansion { negative, integer, prefix, repetend }
+    }
+
+    // The expansion of a fraction of machine integers.
+    pub fn from_fraction(p: i64, q: i64) -> DecimalExpansion {
+        DecimalExpansion::new(&Decimal::from(p), &Decimal::from(q))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // The magnitude of the whole number part, before the point.
+    pub fn integer_part(&self) -> &Decimal {
+        &self.integer
+    }
+
+    // The digits after the point that are not repeated.
+    pub fn prefix(&self) -> &[u8] {
+        &self.prefix
+    }
+
+    // The digits that repeat forever after the prefix, or nothing if the expansion terminates.
+    pub fn repetend(&self) -> &[u8] {
+        &self.repetend
+    }
+
+    // The length of the recurring cycle, which is 0 for a terminating expansion.
+    pub fn cycle_length(&self) -> usize {
+        self.repetend.len()
+    }
+
+    pub fn is_terminating(&self) -> bool {
+        self.repetend.is_empty()
+    }
+
+    // All the digits after the point, forever.
+    //
+    // A terminating expansion continues with zeros.
+    pub fn digits(&self) -> impl '_ + Iterator<Item = u8> {
+        let tail: Box<dyn Iterator<Item = u8>> = if self.repetend.is_empty() {
+            Box::new(std::iter::repeat(0))
+        } else {
+            Box::new(self.repetend.iter().copied().cycle())
+        };
+        self.prefix.iter().copied().chain(tail)
+    }
+
+    // The expansion written out to exactly `places` digits after the point, truncated rather than rounded.
+    pub fn to_places(&self, places: usize) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        let fraction: String = self.digits().take(places).map(|d| char::from(b'0' + d)).collect();
+        if places == 0 {
+            format!("{}{}", sign, self.integer)
+        } else {
+            format!("{}{}.{}", sign, self.integer, fraction)
+        }
+    }
+}
+
+// Lazily stream the digits of `|p/q|` after the point, by long division, forever.
+//
+// Unlike `DecimalExpansion`, this doesn't look for the recurring cycle,
+// so it is the way to get the first few hundred places of a fraction with a huge denominator.
+//
+// Panics if `q` is zero.
+pub fn fraction_digits(p: &Decimal, q: &Decimal) -> impl Iterator<Item = u8> {
+    assert!(!q.is_zero(), "attempt to divide by zero");
+
+    let q = q.abs();
+    let mut r = &p.abs() % &q;
+    let ten = Decimal::from(10u32);
+    std::iter::from_fn(move || {
+        let (d, r_next) = (&r * &ten).div_rem(&q);
+        r = r_next;
+        Some(u8::try_from(&d).unwrap())
+    })
+}
+
+// Divide out all the factors of `p` from `n`, returning how many there were and what is left.
+fn strip_factor(n: &Decimal, p: u32) -> (u32, Decimal) {
+    let mut count = 0;
+    let mut n = n.clone();
+    loop {
+        let (q, r) = n.div_rem_small(p);
+        if r != 0 { return (count, n); }
+        count += 1;
+        n = q;
+    }
+}
+
+// The conventional notations for a recurring decimal.
+//
+// By default the repetend is put in parentheses, as `0.1(6)`.
+// The alternate flag `{:#}` draws a line over it instead, as `0.16̅`, using a combining overline after each digit.
+impl std::fmt::Display for DecimalExpansion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.integer)?;
+        if self.prefix.is_empty() && self.repetend.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, ".")?;
+        for d in &self.prefix {
+            write!(f, "{}", d)?;
+        }
+        if self.repetend.is_empty() {
+            return Ok(());
+        }
+
+        if f.alternate() {
+            for d in &self.repetend {
+                write!(f, "{}\u{0305}", d)?;
+            }
+            Ok(())
+        } else {
+            write!(f, "(")?;
+            for d in &self.repetend {
+                write!(f, "{}", d)?;
+            }
+            write!(f, ")")
+        }
+    }
+}
@@ -1,4 +1,10 @@
 pub mod calendar;
+pub mod continued_fraction;
 pub mod decimal;
+pub mod expansion;
+pub mod modular;
 pub mod number_words;
 pub mod numbers;
+pub mod pythagorean;
+pub mod rational;
+pub mod sieve;
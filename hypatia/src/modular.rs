@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::numbers::{isqrt, Factors, Primes};
+
+// The greatest common divisor, by Stein's binary algorithm.
+//
+// This only shifts and subtracts, with no division.
+// The common powers of two are taken out first, and then the odd parts are repeatedly differenced,
+// shifting out the powers of two from each difference.
+// The gcd of zero and `n` is `n`.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    let mut a = a >> a.trailing_zeros();
+    let mut b = b;
+    while b != 0 {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+    }
+    a << shift
+}
+
+// The least common multiple, or `None` if it doesn't fit in a `u64`.
+//
+// The lcm of zero and anything is zero.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+// The gcd `g` of `a` and `b`, along with Bézout coefficients `x` and `y` such that `a*x + b*y = g`.
+//
+// This runs Euclid's algorithm, keeping track of how each remainder is made from `a` and `b`.
+// The coefficients are bounded by `b/g` and `a/g`, so they always fit in an `i128`.
+pub fn extended_gcd(a: u64, b: u64) -> (u64, i128, i128) {
+    let (mut r0, mut r1) = (i128::from(a), i128::from(b));
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    (r0 as u64, x0, y0)
+}
+
+// The inverse of `a` modulo `m`, the `x` in `0 .. m` with `a*x = 1 (mod m)`.
+//
+// This only exists when `a` and `m` are coprime, and is `None` otherwise.
+//
+// Panics if `m` is zero.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    assert!(m != 0, "inverse modulo zero");
+    let (g, x, _) = extended_gcd(a % m, m);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(i128::from(m)) as u64)
+}
+
+// `a * b mod m`, without overflow, by widening to a `u128`.
+pub fn mod_mul(a: u64, b: u64, m: u64) -> u64 {
+    mod_mul_u128(u128::from(a), u128::from(b), u128::from(m)) as u64
+}
+
+// `base^exp mod m`, by square and multiply.
+//
+// Panics if `m` is zero.
+pub fn mod_pow(base: u64, exp: u64, m: u64) -> u64 {
+    mod_pow_u128(u128::from(base), u128::from(exp), u128::from(m)) as u64
+}
+
+// `a * b mod m` for `u128`s, without overflow.
+//
+// When both fit in 64 bits the product fits in a `u128`.
+// Otherwise it is built up by doubling and adding, with each step kept below `m` so that nothing overflows.
+//
+// Panics if `m` is zero.
+pub fn mod_mul_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a <= u128::from(u64::MAX) && b <= u128::from(u64::MAX) {
+        return a * b % m;
+    }
+
+    let (a, b) = (a % m, b % m);
+    let add_mod = |x: u128, y: u128| if x >= m - y { x - (m - y) } else { x + y };
+    let mut result = 0;
+    for bit in (0..128 - b.leading_zeros()).rev() {
+        result = add_mod(result, result);
+        if b >> bit & 1 == 1 {
+            result = add_mod(result, a);
+        }
+    }
+    result
+}
+
+// `base^exp mod m` for `u128`s, by square and multiply.
+//
+// Panics if `m` is zero.
+pub fn mod_pow_u128(base: u128, exp: u128, m: u128) -> u128 {
+    let mut result = 1 % m;
+    let mut base = base % m;
+    let mut e = exp;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mod_mul_u128(result, base, m);
+        }
+        e >>= 1;
+        base = mod_mul_u128(base, base, m);
+    }
+    result
+}
+
+// Solve a system of congruences `x = a_i (mod m_i)` by the Chinese Remainder Theorem.
+//
+// The result is `(x, m)`, where `m` is the lcm of the moduli and `x` in `0 .. m` is the unique solution modulo `m`.
+// The moduli don't need to be coprime. Congruences are merged a pair at a time:
+// `x = a1 (mod m1)` and `x = a2 (mod m2)` have a common solution exactly when `g = gcd(m1, m2)` divides `a2 - a1`,
+// and then it is `a1 + m1*k`, where `k = (a2 - a1)/g * (m1/g)^-1 (mod m2/g)`.
+//
+// This is `None` if the congruences are inconsistent, or if the lcm of the moduli doesn't fit in a `u64`.
+// An empty system is solved by anything, so gives `(0, 1)`.
+//
+// Panics if any modulus is zero.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences.iter().try_fold((0u64, 1u64), |(a1, m1), &(a2, m2)| {
+        assert!(m2 != 0, "congruence modulo zero");
+        let a2 = a2 % m2;
+        let g = gcd(m1, m2);
+        let diff = i128::from(a2) - i128::from(a1);
+        if diff % i128::from(g) != 0 {
+            return None;
+        }
+
+        let m = lcm(m1, m2)?;
+        let m2_g = m2 / g;
+        // m1/g and m2/g are coprime, so the inverse always exists
+        let inv = mod_inverse(m1 / g, m2_g).unwrap();
+        let k = (diff / i128::from(g)).rem_euclid(i128::from(m2_g)) as u64;
+        let k = mod_mul(k, inv, m2_g);
+        let x = (u128::from(a1) + u128::from(m1) * u128::from(k)) % u128::from(m);
+        Some((x as u64, m))
+    })
+}
+
+// Carmichael's function lambda(n), the exponent of the multiplicative group modulo `n`.
+//
+// This is the smallest `m` with `a^m = 1 (mod n)` for every `a` coprime to `n`, so it divides the totient.
+// For odd prime powers, and 2 and 4, it is the totient, `p^(e-1) * (p-1)`.
+// The higher powers of two have no primitive root, and lambda(2^e) is half the totient, `2^(e-2)`.
+// For anything else it is the lcm over the prime powers.
+//
+// Panics if `n` is zero.
+pub fn carmichael_lambda(n: u64) -> u64 {
+    assert!(n != 0, "carmichael function of zero");
+    Primes::global().factorise(n).factors.iter()
+        .map(|f| {
+            if f.prime == 2 && f.power >= 3 {
+                1 << (f.power - 2)
+            } else {
+                f.prime.pow(f.power - 1) * (f.prime - 1)
+            }
+        })
+        // each term divides the totient, so neither does their lcm overflow
+        .fold(1, |l, t| lcm(l, t).unwrap())
+}
+
+// The multiplicative order of `a` modulo `n`, the smallest `k > 0` with `a^k = 1 (mod n)`.
+//
+// The order divides lambda(n), so start from that and divide out each of its prime factors while the power stays 1.
+// This is `None` when `a` isn't coprime to `n`, as then no power of it is 1.
+//
+// Panics if `n` is zero.
+pub fn multiplicative_order(a: u64, n: u64) -> Option<u64> {
+    assert!(n != 0, "multiplicative order modulo zero");
+    if gcd(a % n, n) != 1 {
+        return None;
+    }
+    let lambda = carmichael_lambda(n);
+    Some(reduce_exponent(a, n, lambda, &Primes::global().factorise(lambda).factors))
+}
+
+// The smallest divisor `k` of `e` with `a^k = 1 (mod n)`, given that `a^e = 1` and the factors of `e`.
+fn reduce_exponent(a: u64, n: u64, e: u64, factors: &Factors) -> u64 {
+    let mut k = e;
+    for f in factors.iter() {
+        for _ in 0..f.power {
+            if mod_pow(a, k / f.prime, n) != 1 {
+                break;
+            }
+            k /= f.prime;
+        }
+    }
+    k
+}
+
+// The smallest primitive root modulo `n`, a `g` whose powers run through every residue coprime to `n`.
+//
+// These exist only for 1, 2, 4, and odd prime powers and their doubles, exactly when lambda(n) = phi(n), and otherwise this is `None`.
+// A candidate `g` is a primitive root when `g^(phi/q) != 1` for each prime `q` dividing phi(n), as then its order can't be a proper divisor.
+// The smallest is always small, so trying each in turn is quick.
+//
+// Panics if `n` is zero.
+pub fn primitive_root(n: u64) -> Option<u64> {
+    assert!(n != 0, "primitive root modulo zero");
+    if n == 1 {
+        return Some(0);
+    }
+    let factors = Primes::global().factorise(n).factors;
+    let phi = factors.totient();
+    if carmichael_lambda(n) != phi {
+        return None;
+    }
+
+    let phi_factors = Primes::global().factorise(phi).factors;
+    (1..n).find(|&g| {
+        gcd(g, n) == 1 && phi_factors.iter().all(|f| mod_pow(g, phi / f.prime, n) != 1)
+    })
+}
+
+// The discrete logarithm: the smallest `x >= 0` with `a^x = b (mod n)`, or `None` if there isn't one.
+//
+// This is Shanks' baby-step giant-step, which takes O(sqrt(n)) time and space.
+// Writing `x = i*m - j` with `m = ceil(sqrt(n))`, the baby steps `b * a^j` for `j < m` go into a table,
+// and then the giant steps `a^(i*m)` for `i = 1, 2, ...` are looked up in it.
+//
+// That needs `a` to be invertible, so any common factor `g` of `a` and `n` is first divided out of the congruence,
+// which leaves `(a/g) * a^(x-1) = b/g (mod n/g)` and so on until `a` is coprime to what's left of `n`.
+//
+// Panics if `n` is zero.
+pub fn discrete_log(a: u64, b: u64, n: u64) -> Option<u64> {
+    assert!(n != 0, "discrete logarithm modulo zero");
+    let mut n = n;
+    let a = a % n;
+    let mut b = b % n;
+
+    // a^x = b / scale (mod n), after `offset` steps of dividing out common factors
+    let mut scale = 1 % n;
+    let mut offset = 0;
+    loop {
+        if scale == b {
+            return Some(offset);
+        }
+        let g = gcd(a, n);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        b /= g;
+        n /= g;
+        scale = mod_mul(a / g, scale % n, n);
+        offset += 1;
+    }
+
+    let m = isqrt(n - 1) + 1;
+    let mut baby = HashMap::new();
+    let mut bj = b % n;
+    for j in 0..m {
+        // a later j gives a smaller x for the same i
+        baby.insert(bj, j);
+        bj = mod_mul(bj, a, n);
+    }
+
+    let giant = mod_pow(a, m, n);
+    let mut gi = scale % n;
+    for i in 1..=m {
+        gi = mod_mul(gi, giant, n);
+        if let Some(&j) = baby.get(&gi) {
+            return Some(offset + i * m - j);
+        }
+    }
+    None
+}
+
+// The Jacobi symbol (a/n), for odd `n`.
+//
+// This extends the Legendre symbol multiplicatively to odd composites.
+// It is worked out like a gcd, using quadratic reciprocity to swap `a` and `n`,
+// and the supplementary law (2/n) = 1 exactly when `n` is 1 or 7 mod 8 to take out factors of 2.
+// It is 0 when `a` and `n` have a common factor.
+// If it is -1 then `a` is certainly not a square modulo `n`, but for composite `n` a 1 doesn't mean that it is.
+//
+// Panics if `n` is even.
+pub fn jacobi(a: u64, n: u64) -> i8 {
+    assert!(n % 2 == 1, "jacobi symbol with an even modulus");
+    let mut a = a % n;
+    let mut n = n;
+    let mut result = 1;
+    while a != 0 {
+        let twos = a.trailing_zeros();
+        a >>= twos;
+        if twos % 2 == 1 && (n % 8 == 3 || n % 8 == 5) {
+            result = -result;
+        }
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        a %= n;
+    }
+    if n == 1 { result } else { 0 }
+}
+
+// The Legendre symbol (a/p) for an odd prime `p`: 1 if `a` is a non-zero square modulo `p`, -1 if it isn't, and 0 if `p` divides it.
+//
+// For a prime, this is the same as the Jacobi symbol.
+//
+// Panics if `p` is even.
+pub fn legendre(a: u64, p: u64) -> i8 {
+    jacobi(a, p)
+}
+
+// A square root of `a` modulo the prime `p`, by the Tonelli-Shanks algorithm, or `None` if `a` isn't a square.
+//
+// For `p = 3 (mod 4)` the root is just `a^((p+1)/4)`.
+// Otherwise, write `p - 1 = q * 2^s` with `q` odd. Then `r = a^((q+1)/2)` is a root up to the error `t = a^q`,
+// which lies in the subgroup of order `2^s`. Powers of a non-residue generate that subgroup,
+// and are used to push the order of `t` down a power of two at a time until it is 1.
+//
+// This is the smaller of the two roots `r` and `p - r`. Whether `p` is prime isn't checked.
+pub fn tonelli_shanks(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if legendre(a, p) != 1 {
+        return None;
+    }
+    if p % 4 == 3 {
+        return Some(smaller_root(mod_pow(a, (p + 1) / 4, p), p));
+    }
+
+    let s = (p - 1).trailing_zeros();
+    let q = (p - 1) >> s;
+    let z = (2..p).find(|&z| legendre(z, p) == -1).unwrap();
+
+    let mut m = s;
+    let mut c = mod_pow(z, q, p);
+    let mut t = mod_pow(a, q, p);
+    let mut r = mod_pow(a, q.div_ceil(2), p);
+    while t != 1 {
+        // the least i with t^(2^i) = 1
+        let mut i = 0;
+        let mut t2i = t;
+        while t2i != 1 {
+            t2i = mod_mul(t2i, t2i, p);
+            i += 1;
+        }
+        let b = mod_pow(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mod_mul(b, b, p);
+        t = mod_mul(t, c, p);
+        r = mod_mul(r, b, p);
+    }
+    Some(smaller_root(r, p))
+}
+
+// A square root of `a` modulo the prime `p`, by Cipolla's algorithm, or `None` if `a` isn't a square.
+//
+// Find a `t` where `w = t^2 - a` isn't a square, and work in the field of `x + y*sqrt(w)`.
+// There, `(t + sqrt(w))^((p+1)/2)` is a square root of `a` that happens to lie in the integers mod `p`.
+// Unlike Tonelli-Shanks, this doesn't slow down when `p - 1` has a large power of two in it.
+//
+// This is the smaller of the two roots `r` and `p - r`. Whether `p` is prime isn't checked.
+pub fn cipolla(a: u64, p: u64) -> Option<u64> {
+    let a = a % p;
+    if p == 2 || a == 0 {
+        return Some(a);
+    }
+    if legendre(a, p) != 1 {
+        return None;
+    }
+
+    let sub = |x: u64, y: u64| if x >= y { x - y } else { p - (y - x) };
+    let add = |x: u64, y: u64| sub(x, p - y);
+    // stumbling on a `t` with `t^2 = a` would also do
+    let (t, w) = (1..p)
+        .map(|t| (t, sub(mod_mul(t, t, p), a)))
+        .find(|&(_, w)| w == 0 || legendre(w, p) == -1)
+        .unwrap();
+    if w == 0 {
+        return Some(smaller_root(t, p));
+    }
+
+    // (x1 + y1 sqrt(w)) * (x2 + y2 sqrt(w))
+    let mul = |(x1, y1): (u64, u64), (x2, y2): (u64, u64)| (
+        add(mod_mul(x1, x2, p), mod_mul(mod_mul(y1, y2, p), w, p)),
+        add(mod_mul(x1, y2, p), mod_mul(x2, y1, p)),
+    );
+    let mut result = (1, 0);
+    let mut base = (t, 1);
+    let mut e = p.div_ceil(2);
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul(result, base);
+        }
+        e >>= 1;
+        base = mul(base, base);
+    }
+    Some(smaller_root(result.0, p))
+}
+
+fn smaller_root(r: u64, p: u64) -> u64 {
+    r.min(p - r)
+}
+
+// All the square roots of `a` modulo `p^e`, for a prime `p`, in increasing order.
+//
+// Roots of a unit modulo `p` lift uniquely to higher powers by Hensel's lemma, `x -> x - (x^2 - a)/(2x)`,
+// except for `p = 2`, where the roots modulo `2^e` are `±r` and `±r + 2^(e-1)` for an `r` found a bit at a time.
+// When `p^v` divides `a`, for `v < e`, then `v` must be even, and the roots are `p^(v/2)` times roots of `a/p^v`,
+// each of which is only fixed modulo `p^(e-v)`, so gives several roots modulo `p^e`.
+// When `p^e` divides `a`, the roots are the multiples of `p^ceil(e/2)`.
+//
+// Panics if `p^e` doesn't fit in a `u64`. Whether `p` is prime isn't checked.
+pub fn sqrt_mod_prime_power(a: u64, p: u64, e: u32) -> Vec<u64> {
+    let pe = p.checked_pow(e).expect("prime power modulus overflows u64");
+    let a = a % pe;
+
+    if a == 0 {
+        let step = p.pow(e.div_ceil(2));
+        return (0..pe / step).map(|k| k * step).collect();
+    }
+
+    let mut v = 0;
+    let mut unit = a;
+    while unit % p == 0 {
+        unit /= p;
+        v += 1;
+    }
+    if v % 2 == 1 {
+        return Vec::new();
+    }
+
+    let scale = p.pow(v / 2);
+    let unit_modulus = p.pow(e - v);
+    let mut roots: Vec<u64> = sqrt_unit_mod_prime_power(unit, p, e - v)
+        .into_iter()
+        .flat_map(|y| (0..scale).map(move |t| (y + t * unit_modulus) * scale))
+        .collect();
+    roots.sort_unstable();
+    roots.dedup();
+    roots
+}
+
+// The square roots of `a` modulo `p^e`, where `a` is coprime to `p`.
+fn sqrt_unit_mod_prime_power(a: u64, p: u64, e: u32) -> Vec<u64> {
+    let pe = p.pow(e);
+    if p == 2 {
+        return match e {
+            0 | 1 => vec![a % pe],
+            2 => if a % 4 == 1 { vec![1, 3] } else { Vec::new() },
+            _ => {
+                if a % 8 != 1 {
+                    return Vec::new();
+                }
+                // r is a root modulo 2^k, and if it isn't one modulo 2^(k+1) then r + 2^(k-1) is
+                let mut r = 1u64;
+                for k in 3..e {
+                    if mod_mul(r, r, 1 << (k + 1)) != a % (1 << (k + 1)) {
+                        r += 1 << (k - 1);
+                    }
+                }
+                let half = pe / 2;
+                vec![r, pe - r, (r + half) % pe, (pe - r + half) % pe]
+            }
+        };
+    }
+
+    let mut r = match tonelli_shanks(a, p) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+    let mut pk = p;
+    for _ in 1..e {
+        pk *= p;
+        // x^2 - a is divisible by the previous power, so this is an exact correction
+        let f = (u128::from(r) * u128::from(r) + u128::from(pk - a % pk)) % u128::from(pk);
+        let inv = mod_inverse(2 * r % pk, pk).unwrap();
+        r = (u128::from(r) + u128::from(pk) - u128::from(mod_mul(f as u64, inv, pk))) as u64 % pk;
+    }
+    vec![r.min(pe - r), r.max(pe - r)]
+}
+
+// All the square roots of `a` modulo `n`, in increasing order.
+//
+// The roots modulo each prime power in `n` are found by `sqrt_mod_prime_power`,
+// and every combination of them is put back together by the Chinese Remainder Theorem.
+// There can be a lot of roots for an `n` with many prime factors.
+//
+// Panics if `n` is zero.
+pub fn sqrt_mod(a: u64, n: u64) -> Vec<u64> {
+    assert!(n != 0, "square root modulo zero");
+    let mut roots = vec![(0u64, 1u64)];
+    for f in Primes::global().factorise(n).factors.iter() {
+        let pe = f.prime.pow(f.power);
+        let local = sqrt_mod_prime_power(a, f.prime, f.power);
+        roots = roots.iter()
+            .flat_map(|&(x, m)| local.iter().map(move |&r| crt(&[(x, m), (r, pe)]).unwrap()))
+            .collect();
+    }
+    let mut roots: Vec<u64> = roots.into_iter().map(|(x, _)| x).collect();
+    roots.sort_unstable();
+    roots
+}
+
+// An integer modulo `M`, always kept reduced into `0 .. M`.
+//
+// The arithmetic operators all work modulo `M`, with products going through `u128` so that nothing overflows.
+// Division multiplies by the inverse, so it only works for divisors coprime to `M`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mod<const M: u64>(u64);
+
+impl<const M: u64> Mod<M> {
+    pub fn new(n: u64) -> Mod<M> {
+        Mod(n % M)
+    }
+
+    // The representative in `0 .. M`.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Mod<M> {
+        Mod(mod_pow(self.0, exp, M))
+    }
+
+    // The multiplicative inverse, if this is coprime to `M`.
+    pub fn inverse(self) -> Option<Mod<M>> {
+        mod_inverse(self.0, M).map(Mod)
+    }
+}
+
+impl<const M: u64> From<u64> for Mod<M> {
+    fn from(n: u64) -> Mod<M> {
+        Mod::new(n)
+    }
+}
+
+impl<const M: u64> Add for Mod<M> {
+    type Output = Mod<M>;
+
+    fn add(self, rhs: Mod<M>) -> Mod<M> {
+        // both are below M, so this only needs taking back into range once, and the sum can't overflow a u128
+        Mod(((u128::from(self.0) + u128::from(rhs.0)) % u128::from(M)) as u64)
+    }
+}
+
+impl<const M: u64> Sub for Mod<M> {
+    type Output = Mod<M>;
+
+    fn sub(self, rhs: Mod<M>) -> Mod<M> {
+        self + -rhs
+    }
+}
+
+impl<const M: u64> Mul for Mod<M> {
+    type Output = Mod<M>;
+
+    fn mul(self, rhs: Mod<M>) -> Mod<M> {
+        Mod(mod_mul(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Div for Mod<M> {
+    type Output = Mod<M>;
+
+    // Multiply by the inverse.
+    //
+    // Panics if `rhs` isn't coprime to `M`.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Mod<M>) -> Mod<M> {
+        self * rhs.inverse().expect("division by a number not coprime to the modulus")
+    }
+}
+
+impl<const M: u64> Neg for Mod<M> {
+    type Output = Mod<M>;
+
+    fn neg(self) -> Mod<M> {
+        if self.0 == 0 { self } else { Mod(M - self.0) }
+    }
+}
+
+impl<const M: u64> AddAssign for Mod<M> {
+    fn add_assign(&mut self, rhs: Mod<M>) { *self = *self + rhs }
+}
+
+impl<const M: u64> SubAssign for Mod<M> {
+    fn sub_assign(&mut self, rhs: Mod<M>) { *self = *self - rhs }
+}
+
+impl<const M: u64> MulAssign for Mod<M> {
+    fn mul_assign(&mut self, rhs: Mod<M>) { *self = *self * rhs }
+}
+
+impl<const M: u64> DivAssign for Mod<M> {
+    fn div_assign(&mut self, rhs: Mod<M>) { *self = *self / rhs }
+}
+
+impl<const M: u64> std::iter::Sum for Mod<M> {
+    fn sum<I: Iterator<Item = Mod<M>>>(iter: I) -> Mod<M> {
+        iter.fold(Mod::new(0), |l, r| l + r)
+    }
+}
+
+impl<const M: u64> std::iter::Product for Mod<M> {
+    fn product<I: Iterator<Item = Mod<M>>>(iter: I) -> Mod<M> {
+        iter.fold(Mod::new(1), |l, r| l * r)
+    }
+}
+
+impl<const M: u64> std::fmt::Display for Mod<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
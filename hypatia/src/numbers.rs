@@ -1,22 +1,198 @@
 use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::sync::{OnceLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
-pub fn triangular(n: u64) -> u64 {
-    ((n * n) + n) / 2
+use num_traits::{CheckedAdd, CheckedMul, CheckedSub, Num};
+
+use crate::decimal::Decimal;
+use crate::modular::{gcd, mod_mul, mod_mul_u128, mod_pow_u128};
+
+/// The integer types that the functions in this module work over.
+///
+/// These are the unsigned primitives and `Decimal`.
+/// The checked operations are used to spot overflow, which never happens for a `Decimal`.
+pub trait Natural: Clone + Ord + std::fmt::Debug + std::fmt::Display + Num + CheckedAdd + CheckedSub + CheckedMul {}
+
+impl Natural for u8 {}
+impl Natural for u16 {}
+impl Natural for u32 {}
+impl Natural for u64 {}
+impl Natural for u128 {}
+impl Natural for usize {}
+impl Natural for Decimal {}
+
+/// The n'th triangular number, `1 + 2 + ... + n = n(n+1)/2`.
+///
+/// Panics if this overflows.
+pub fn triangular<T: Natural>(n: T) -> T {
+    checked_triangular(n).expect("triangular number overflows")
 }
 
-// fixme: not sure this is correct - see the impl in decimal
-pub fn fib() -> impl Iterator<Item = u64> {
-    let mut f0 = 0;
-    let mut f1 = 1;
+/// The n'th triangular number, or `None` if it overflows.
+///
+/// One of `n` and `n+1` is even, and halving that first means nothing larger than the result is ever computed.
+pub fn checked_triangular<T: Natural>(n: T) -> Option<T> {
+    let two = T::one() + T::one();
+    let n_1 = n.checked_add(&T::one())?;
+    if (n.clone() % two.clone()).is_zero() {
+        (n / two).checked_mul(&n_1)
+    } else {
+        n.checked_mul(&(n_1 / two))
+    }
+}
+
+/// Fibonacci numbers.
+///
+/// These are indexed from 0, as is conventional:
+///
+/// fib(0)=0
+/// fib(1)=1
+/// fib(n)=fib(n-1)+fib(n-2)
+///
+/// so the iterator yields 0, 1, 1, 2, 3, 5, ... and `fib().nth(n)` is `nth_fib(n)`.
+/// `Decimal::fib` and `Decimal::nth_fib` use the same indexing.
+///
+/// The iterator ends at the last number that fits, fib(93) for a `u64`, rather than wrapping around.
+/// For a `Decimal` it never ends.
+pub fn fib<T: Natural>() -> impl Iterator<Item = T> {
+    let mut f0 = Some(T::zero());
+    let mut f1 = Some(T::one());
 
     std::iter::from_fn(move || {
-        let f = f0 + f1;
-        f0 = f1;
-        f1 = f;
+        let f = f0.take()?;
+        f0 = f1.take();
+        f1 = f0.as_ref().and_then(|g| g.checked_add(&f));
         Some(f)
     })
 }
 
+/// The n'th Fibonacci number, or `None` if it doesn't fit in a `u64`.
+///
+/// This uses fast doubling, from the identities
+///
+/// fib(2k)   = fib(k) * (2*fib(k+1) - fib(k))
+/// fib(2k+1) = fib(k)^2 + fib(k+1)^2
+///
+/// which need only O(log n) steps.
+pub fn nth_fib(n: u64) -> Option<u64> {
+    // fib(93) is the largest that fits in a u64, and fib(94) still fits in a u128 for the doubling pairs
+    if n > 93 {
+        return None;
+    }
+    let (f, _) = fib_pair_u128(n as u32);
+    u64::try_from(f).ok()
+}
+
+// (fib(n), fib(n+1)) by fast doubling, from the most significant bit of `n` down.
+fn fib_pair_u128(n: u32) -> (u128, u128) {
+    let mut a = 0u128;
+    let mut b = 1u128;
+    for bit in (0..32 - n.leading_zeros()).rev() {
+        let c = a * (2 * b - a);
+        let d = a * a + b * b;
+        if n >> bit & 1 == 0 {
+            a = c;
+            b = d;
+        } else {
+            a = d;
+            b = c + d;
+        }
+    }
+    (a, b)
+}
+
+/// Lucas sequences.
+///
+/// For integers P and Q, these are the pair of sequences
+///
+/// U(0)=0, U(1)=1, U(n)=P*U(n-1) - Q*U(n-2)
+/// V(0)=2, V(1)=P, V(n)=P*V(n-1) - Q*V(n-2)
+///
+/// Lots of familiar sequences are special cases:
+/// the Fibonacci and Lucas numbers are U and V for P=1, Q=-1,
+/// and the Pell and Pell-Lucas numbers are U and V for P=2, Q=-1.
+/// The terms can be negative, and grow exponentially, so they are `Decimal`s.
+#[derive(Clone, Debug)]
+pub struct Lucas {
+    p: Decimal,
+    q: Decimal,
+}
+
+impl Lucas {
+    pub fn new(p: i64, q: i64) -> Lucas {
+        Lucas { p: Decimal::from(p), q: Decimal::from(q) }
+    }
+
+    pub fn fibonacci() -> Lucas {
+        Lucas::new(1, -1)
+    }
+
+    pub fn pell() -> Lucas {
+        Lucas::new(2, -1)
+    }
+
+    /// U(0), U(1), U(2), ...
+    pub fn u(&self) -> impl Iterator<Item = Decimal> {
+        self.recurrence(Decimal::zero(), Decimal::from(1u32))
+    }
+
+    /// V(0), V(1), V(2), ...
+    pub fn v(&self) -> impl Iterator<Item = Decimal> {
+        self.recurrence(Decimal::from(2u32), self.p.clone())
+    }
+
+    fn recurrence(&self, x0: Decimal, x1: Decimal) -> impl Iterator<Item = Decimal> {
+        let (p, q) = (self.p.clone(), self.q.clone());
+        let mut x0 = x0;
+        let mut x1 = x1;
+
+        std::iter::from_fn(move || {
+            let x2 = &(&p * &x1) - &(&q * &x0);
+            let x = std::mem::replace(&mut x0, std::mem::replace(&mut x1, x2));
+            Some(x)
+        })
+    }
+
+    /// U(n), in O(log n) steps.
+    pub fn nth_u(&self, n: u64) -> Decimal {
+        self.nth(n).0
+    }
+
+    /// V(n), in O(log n) steps.
+    pub fn nth_v(&self, n: u64) -> Decimal {
+        self.nth(n).1
+    }
+
+    // (U(n), V(n)) by doubling, from the most significant bit of `n` down, using
+    //
+    // U(2k)   = U(k)*V(k)
+    // V(2k)   = V(k)^2 - 2*Q^k
+    // U(k+1)  = (P*U(k) + V(k)) / 2
+    // V(k+1)  = (D*U(k) + P*V(k)) / 2
+    //
+    // where D = P^2 - 4Q. The divisions by two are always exact.
+    fn nth(&self, n: u64) -> (Decimal, Decimal) {
+        let two = Decimal::from(2u32);
+        let d = &(&self.p * &self.p) - &(&self.q * &Decimal::from(4u32));
+
+        let mut u = Decimal::zero();
+        let mut v = two.clone();
+        let mut q_k = Decimal::from(1u32);
+        for bit in (0..64 - n.leading_zeros()).rev() {
+            u = &u * &v;
+            v = &(&v * &v) - &(&two * &q_k);
+            q_k = &q_k * &q_k;
+            if n >> bit & 1 == 1 {
+                let u_1 = &(&(&self.p * &u) + &v) / &two;
+                v = &(&(&d * &u) + &(&self.p * &v)) / &two;
+                u = u_1;
+                q_k = &q_k * &self.q;
+            }
+        }
+        (u, v)
+    }
+}
+
 /// Factorials.
 /// 
 /// fact(0)=1
@@ -25,61 +201,236 @@ pub fn fib() -> impl Iterator<Item = u64> {
 /// fact(3)=fact(2)*3=6
 /// 
 /// and so on.
-pub fn factorial() -> impl Iterator<Item = u64> {
-    let mut f = 1;
-    let mut i = 1;
+///
+/// The iterator ends at the last number that fits, fact(20) for a `u64`, rather than wrapping around.
+/// For a `Decimal` it never ends.
+pub fn factorial<T: Natural>() -> impl Iterator<Item = T> {
+    let mut f = Some(T::one());
+    let mut i = T::one();
 
     std::iter::from_fn(move || {
-        let f0 = f;
-        f *= i;
-        i += 1;
+        let f0 = f.take()?;
+        f = f0.checked_mul(&i);
+        i = i.clone() + T::one();
 
         Some(f0)
     })
 }
 
 
+/// Integer square root, the largest `r` with `r*r <= n`.
+///
+/// Unlike going through `f64`, this is exact for every `u64`.
+pub fn isqrt(n: u64) -> u64 {
+    nth_root_u128(u128::from(n), 2) as u64
+}
+
+pub fn isqrt_u128(n: u128) -> u128 {
+    nth_root_u128(n, 2)
+}
+
+/// Integer cube root, the largest `r` with `r*r*r <= n`.
+pub fn icbrt(n: u64) -> u64 {
+    nth_root_u128(u128::from(n), 3) as u64
+}
+
+pub fn icbrt_u128(n: u128) -> u128 {
+    nth_root_u128(n, 3)
+}
+
+/// Integer k'th root, the largest `r` with `r^k <= n`.
+///
+/// Panics if `k` is zero.
+pub fn nth_root(n: u64, k: u32) -> u64 {
+    nth_root_u128(u128::from(n), k) as u64
+}
+
+// Newton's method on integers.
+//
+// The iteration `x' = ((k-1)x + n/x^(k-1)) / k` decreases monotonically towards the root,
+// as long as it starts from above it. Once it stops decreasing, we have the floor of the root.
+// We start from the power of two that is certainly larger, from the bit length of `n`.
+pub fn nth_root_u128(n: u128, k: u32) -> u128 {
+    assert!(k != 0, "the zeroth root is undefined");
+    if k == 1 || n < 2 {
+        return n;
+    }
+
+    let bits = 128 - n.leading_zeros();
+    let shift = bits.div_ceil(k);
+
+    let k_1 = u128::from(k - 1);
+    let mut x = 1u128 << shift;
+    loop {
+        // if x^(k-1) overflows, then n / x^(k-1) is zero
+        let d = x.checked_pow(k - 1).map_or(0, |p| n / p);
+        let y = (k_1 * x + d) / u128::from(k);
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+pub fn is_perfect_square(n: u64) -> bool {
+    let r = isqrt(n);
+    r * r == n
+}
+
+/// Is this `m^k` for some `m` and some `k >= 2`?
+///
+/// 0 and 1 count, as they are their own squares.
+/// It is enough to try prime `k`, since `m^(ab) = (m^a)^b`.
+pub fn is_perfect_power(n: u64) -> bool {
+    if n < 2 {
+        return true;
+    }
+    let bits = 64 - n.leading_zeros();
+    (2..=bits)
+        .filter(|&k| (2..k).all(|d| k % d != 0))
+        .any(|k| nth_root(n, k).checked_pow(k) == Some(n))
+}
+
+/// The first thirteen primes, as Miller-Rabin witnesses.
+///
+/// A number below 3,317,044,064,679,887,385,961,981 that is a strong probable prime to all of these bases is prime,
+/// which covers every `u64` and the bottom of the `u128` range.
+/// The first twelve, stopping at 37, are only enough below 318,665,857,834,031,151,167,461.
+pub(crate) const MILLER_RABIN_WITNESSES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Is `n` prime?
+///
+/// This is a deterministic Miller-Rabin test, so it takes a handful of modular exponentiations
+/// rather than needing the primes up to the square root of `n`.
+pub fn is_prime(n: u64) -> bool {
+    is_probable_prime_u128(u128::from(n))
+}
+
+/// Is `n` a strong probable prime to each of the bases in `MILLER_RABIN_WITNESSES`?
+///
+/// Composites are always rejected below 3.3 * 10^24, so this is exact up to there.
+/// Above that a composite can slip through.
+pub fn is_probable_prime_u128(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in MILLER_RABIN_WITNESSES.iter().map(|&p| u128::from(p)) {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    // n - 1 = d * 2^s, with d odd
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    MILLER_RABIN_WITNESSES.iter().all(|&a| {
+        let mut x = mod_pow_u128(u128::from(a), d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = mod_mul_u128(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    })
+}
+
 #[derive(Debug)]
-pub struct Factorisation {
-    pub n: u64,
-    pub factors: Factors
+pub struct Factorisation<T = u64> {
+    pub n: T,
+    pub factors: Factors<T>
 }
 
-impl Factorisation {
+impl<T: Natural> Factorisation<T> {
 
     // The proper divisors exclude the number itself.
-    pub fn sum_proper_divisors(&self) -> u64 {
-        self.factors.sum_divisors() - self.n
+    //
+    // Panics if the sum overflows, which can happen for abundant numbers near the top of the range.
+    pub fn sum_proper_divisors(&self) -> T {
+        self.factors.sum_divisors() - self.n.clone()
     }
 
     // Check if the number is perfect or not.
+    //
+    // This is right even when the divisor sum overflows.
+    // In that case, the proper divisors are taken away from `n` one at a time, and it is abundant if they run out before `n` does.
     pub fn perfection(&self) -> Perfection {
-        match self.sum_proper_divisors().cmp(&self.n) {
-            Ordering::Less => Perfection::Deficient,
-            Ordering::Equal => Perfection::Perfect,
-            Ordering::Greater => Perfection::Abundant,
+        let sum_twice = self.factors.checked_sum_divisors().zip(self.n.checked_add(&self.n));
+        if let Some((sum, twice)) = sum_twice {
+            return match sum.cmp(&twice) {
+                Ordering::Less => Perfection::Deficient,
+                Ordering::Equal => Perfection::Perfect,
+                Ordering::Greater => Perfection::Abundant,
+            };
+        }
+
+        let mut left = self.n.clone();
+        for d in self.factors.proper_divisors() {
+            if d > left {
+                return Perfection::Abundant;
+            }
+            left = left - d;
         }
+        if left.is_zero() { Perfection::Perfect } else { Perfection::Deficient }
     }
 
 }
 
+impl Factorisation<u64> {
+    // The sum of the proper divisors, which always fits in a `u128`.
+    pub fn sum_proper_divisors_u128(&self) -> u128 {
+        self.factors.sum_divisors_u128() - u128::from(self.n)
+    }
+}
+
 #[derive(Debug)]
-pub struct Factors(Vec<Factor>);
+pub struct Factors<T = u64>(pub(crate) Vec<Factor<T>>);
+
+impl<T: Natural> Factors<T> {
+    pub fn iter(&self) -> std::slice::Iter<'_, Factor<T>> { self.0.iter() }
 
-impl Factors {
-    pub fn iter(&self) -> std::slice::Iter<'_, Factor> { self.0.iter() }
+    // Factors from prime powers in any order, sorted by prime and with the powers of any repeated primes added together.
+    // Primes to the power zero don't divide anything, so are dropped.
+    //
+    // The primes aren't checked.
+    pub fn new(factors: Vec<Factor<T>>) -> Factors<T> {
+        let mut factors = factors;
+        factors.retain(|f| f.power > 0);
+        factors.sort_by(|l, r| l.prime.cmp(&r.prime));
+        let mut merged: Vec<Factor<T>> = Vec::with_capacity(factors.len());
+        for f in factors {
+            match merged.last_mut() {
+                Some(m) if m.prime == f.prime => m.power += f.power,
+                _ => merged.push(f),
+            }
+        }
+        Factors(merged)
+    }
 
     // Convert this factors into the equivalent factorisation.
     //
     // This calculates `n` by products.
     // It consumes this object, since we should really either be working in a field with `n` being carried around, or be working directly on the factors, but not flipping between them.
-    pub fn factorisation(self) -> Factorisation {
+    //
+    // Panics if `n` overflows.
+    pub fn factorisation(self) -> Factorisation<T> {
         Factorisation {
-            n: self.iter().map(Factor::calculate).product(),
+            n: self.checked_product().expect("factorisation overflows"),
             factors: self
         }
     }
 
+    // The number these are the factors of, or `None` if it overflows.
+    //
+    // Factors made by combining others, such as by `smallest_common_multiple`, can multiply out to more than fits.
+    pub fn checked_product(&self) -> Option<T> {
+        self.iter().try_fold(T::one(), |n, f| n.checked_mul(&f.checked_calculate()?))
+    }
+
     // The count of divisors can be calulated efficiently from the prime powers.
     // For each prime in the prime factors with a power of p, it can contribute p+1 modulo families of divisors.
     // Therefore, the total number of divisors a number has is the product of p+1 for each prime factor power.
@@ -101,14 +452,135 @@ impl Factors {
     // so we reduce the number of sums from polynomial to linear over the factors.
     //
     // We an further optimize this, since the sum of powers is a geometric sum, hence we can solve it analytically without looping.
-    pub fn sum_divisors(&self) -> u64 {
-        self.iter().map(Factor::power_sum).product()
+    //
+    // Panics if the sum overflows. The sum of the divisors can be several times the number itself,
+    // so use `checked_sum_divisors`, or `sum_divisors_u128` for `u64`s, near the top of the range.
+    pub fn sum_divisors(&self) -> T {
+        self.checked_sum_divisors().expect("sum of divisors overflows")
+    }
+
+    pub fn checked_sum_divisors(&self) -> Option<T> {
+        self.iter().try_fold(T::one(), |s, f| s.checked_mul(&f.checked_power_sum()?))
+    }
+
+    // The sum of the k'th powers of the divisors, sigma_k.
+    //
+    // As with `sum_divisors`, which is sigma_1, this is a product of geometric sums, here in `p^k` for each prime `p`.
+    // sigma_0 counts the divisors.
+    //
+    // Panics if the sum overflows.
+    pub fn sigma(&self, k: u32) -> T {
+        self.iter()
+            .try_fold(T::one(), |s, f| {
+                let pk = Factor { prime: num_traits::checked_pow(f.prime.clone(), k as usize)?, power: f.power };
+                s.checked_mul(&pk.checked_power_sum()?)
+            })
+            .expect("sum of divisor powers overflows")
+    }
+
+    // Euler's totient, the count of numbers up to `n` that are coprime to it.
+    //
+    // Each prime power `p^e` contributes `p^(e-1) * (p-1)`, the numbers below it that aren't multiples of `p`.
+    pub fn totient(&self) -> T {
+        self.iter()
+            .filter(|f| f.power > 0)
+            .map(|f| num_traits::pow(f.prime.clone(), f.power as usize - 1) * (f.prime.clone() - T::one()))
+            .fold(T::one(), |t, x| t * x)
+    }
+
+    // The Möbius function: 0 if any prime divides `n` more than once, otherwise -1 or 1 for an odd or even number of primes.
+    pub fn mobius(&self) -> i8 {
+        if self.iter().any(|f| f.power > 1) {
+            0
+        } else if self.0.len() % 2 == 1 {
+            -1
+        } else {
+            1
+        }
+    }
+
+    // The number of distinct primes, omega.
+    pub fn count_distinct_prime_factors(&self) -> u32 {
+        self.0.len() as u32
+    }
+
+    // The number of primes counted with their powers, big omega.
+    pub fn count_prime_factors(&self) -> u32 {
+        self.iter().map(|f| f.power).sum()
+    }
+
+    // All the divisors, including 1 and the number itself, in no particular order.
+    //
+    // These are built up from the prime powers, a prime at a time, by multiplying each divisor so far by each power of the next prime.
+    // There's no trial division, and each divisor is made exactly once.
+    pub fn divisors(&self) -> impl Iterator<Item = T> {
+        self.divisor_products(|f| 0..=f.power).into_iter()
+    }
+
+    // All the divisors, in increasing order.
+    pub fn sorted_divisors(&self) -> impl Iterator<Item = T> {
+        let mut ds = self.divisor_products(|f| 0..=f.power);
+        ds.sort_unstable();
+        ds.into_iter()
+    }
+
+    // The divisors other than the number itself, in increasing order.
+    pub fn proper_divisors(&self) -> impl Iterator<Item = T> {
+        let mut ds = self.divisor_products(|f| 0..=f.power);
+        ds.sort_unstable();
+        ds.pop();
+        ds.into_iter()
+    }
+
+    // The unitary divisors, in increasing order.
+    //
+    // A divisor `d` of `n` is unitary when it is coprime to `n/d`, so each prime power is either all in `d` or not at all.
+    pub fn unitary_divisors(&self) -> impl Iterator<Item = T> {
+        let mut ds = self.divisor_products(|f| [0, f.power]);
+        ds.sort_unstable();
+        ds.into_iter()
+    }
+
+    // The divisors that are perfect squares, in increasing order.
+    pub fn square_divisors(&self) -> impl Iterator<Item = T> {
+        let mut ds = self.divisor_products(|f| (0..=f.power).step_by(2));
+        ds.sort_unstable();
+        ds.into_iter()
+    }
+
+    // The pairs `(d, n/d)` of divisors, with `d <= n/d`, in increasing order of `d`.
+    pub fn divisor_pairs(&self) -> impl Iterator<Item = (T, T)> {
+        let n = self.checked_product().expect("divisors of a number that overflows");
+        self.sorted_divisors()
+            .map(move |d| {
+                let e = n.clone() / d.clone();
+                (d, e)
+            })
+            .take_while(|(d, e)| d <= e)
+    }
+
+    // The products of a choice of power of each prime, with `powers` giving the powers to choose between.
+    fn divisor_products<F, I>(&self, powers: F) -> Vec<T>
+    where
+        F: Fn(&Factor<T>) -> I,
+        I: IntoIterator<Item = u32>,
+    {
+        let mut ds = vec![T::one()];
+        for f in self.iter() {
+            ds = powers(f).into_iter()
+                .flat_map(|e| {
+                    let pe = num_traits::pow(f.prime.clone(), e as usize);
+                    ds.iter().map(move |d| d.clone() * pe.clone())
+                })
+                .collect();
+        }
+        ds
     }
 
     // Iterate over two prime factorisations, returning the factors and their powers in each of the inputs, in order.
     //
     // For factors that appear in one or the other fatorisation but not both, the power in the absent one is 0.
-    pub fn factors_iterator<'a>(&'a self, other: &'a Self) -> impl 'a + Iterator<Item=(u64, (u32, u32))> {
+    pub fn factors_iterator<'a>(&'a self, other: &'a Self) -> impl 'a + Iterator<Item=(T, (u32, u32))> {
         let mut lhs = self.iter();
         let mut rhs = other.iter();
 
@@ -120,23 +592,23 @@ impl Factors {
                 (Some(l), Some(r)) => {
                     if l.prime < r.prime {
                         l_next = lhs.next();
-                        Some((l.prime, (l.power, 0)))
+                        Some((l.prime.clone(), (l.power, 0)))
                     } else if l.prime > r.prime {
                         r_next = rhs.next();
-                        Some((r.prime, (0, r.power)))
+                        Some((r.prime.clone(), (0, r.power)))
                     } else {
                         l_next = lhs.next();
                         r_next = rhs.next();
-                        Some((l.prime, (l.power, r.power)))
+                        Some((l.prime.clone(), (l.power, r.power)))
                     }
                 }
                 (Some(l), None) => {
                     l_next = lhs.next();
-                    Some((l.prime, (l.power, 0)))
+                    Some((l.prime.clone(), (l.power, 0)))
                 }
                 (None, Some(r)) => {
                     r_next = rhs.next();
-                    Some((r.prime, (0, r.power)))
+                    Some((r.prime.clone(), (0, r.power)))
                 }
                 (None, None) => None
             }
@@ -151,7 +623,7 @@ impl Factors {
     pub fn largest_common_factor(&self, other: &Self) -> Self {
         Factors(self.factors_iterator(other)
             .map(|(pr, (l, r))| Factor { prime: pr, power: l.min(r) })
-            .filter(|&f| f.power != 0)
+            .filter(|f| f.power != 0)
             .collect()
         )
     }
@@ -167,11 +639,32 @@ impl Factors {
     }
 }
 
+impl Factor<u64> {
+    // The same factor as a `u128`.
+    pub fn widen(&self) -> Factor<u128> {
+        Factor { prime: u128::from(self.prime), power: self.power }
+    }
+}
+
+impl Factors<u64> {
+    // The sum of the divisors as a `u128`.
+    //
+    // For any number that fits in a `u64`, this is always small enough to fit.
+    pub fn sum_divisors_u128(&self) -> u128 {
+        self.widen().sum_divisors()
+    }
+
+    // The same factors as `u128`s, for sums and products that might not fit in a `u64`.
+    pub fn widen(&self) -> Factors<u128> {
+        Factors(self.iter().map(Factor::widen).collect())
+    }
+}
+
 // Multiplication of prime factor representations.
 //
 // This is slightly odd, in that `Mul` is defined on references, but returns the raw type.
-impl std::ops::Mul for &Factors {
-    type Output = Factors;
+impl<T: Natural> std::ops::Mul for &Factors<T> {
+    type Output = Factors<T>;
 
     // The product of two factorised numbers.
     //
@@ -184,7 +677,7 @@ impl std::ops::Mul for &Factors {
 
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Perfection {
     Deficient,
     Perfect,
@@ -192,127 +685,350 @@ pub enum Perfection {
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct Factor {
-    pub prime: u64,
+pub struct Factor<T = u64> {
+    pub prime: T,
     pub power: u32,
 }
 
-impl Factor {
-    pub fn calculate(&self) -> u64 {
-        self.prime.pow(self.power)
+impl<T: Natural> Factor<T> {
+    // The prime power itself.
+    //
+    // Panics if it overflows.
+    pub fn calculate(&self) -> T {
+        self.checked_calculate().expect("prime power overflows")
+    }
+
+    pub fn checked_calculate(&self) -> Option<T> {
+        num_traits::checked_pow(self.prime.clone(), self.power as usize)
     }
 
     // The sum of all powers of a prime, from 0.
     //
-    // This could be calcualted by looping and summing. However, this is the geometric sum `n^0 + n^1 + .. + n^p = (n^(p+1)-1)/(p-1)` which doesn't require looping.
-    pub fn power_sum(&self) -> u64 {
-        let po = self.power;
-        let pr = self.prime;
-
-        let num = pr.pow(po+1) - 1;
-        let den = pr - 1;
+    // This is the geometric sum `p^0 + p^1 + .. + p^e = (p^(e+1)-1)/(p-1)`.
+    // However, `p^(e+1)` can overflow even when the sum fits, so the powers are summed directly instead.
+    // That's at most 128 steps for anything that fits in a primitive.
+    //
+    // Panics if the sum overflows.
+    pub fn power_sum(&self) -> T {
+        self.checked_power_sum().expect("sum of prime powers overflows")
+    }
 
-        num / den
+    pub fn checked_power_sum(&self) -> Option<T> {
+        let mut term = T::one();
+        let mut sum = T::one();
+        for _ in 0..self.power {
+            term = term.checked_mul(&self.prime)?;
+            sum = sum.checked_add(&term)?;
+        }
+        Some(sum)
     }
 }
 
+// The wheel skips everything with a factor of 2, 3 or 5.
+// Of each 30 consecutive numbers, only these 8 residues can be prime (apart from 2, 3 and 5 themselves),
+// so each byte of a sieve segment covers 30 numbers, one bit per residue.
+const WHEEL: u64 = 30;
+const WHEEL_RESIDUES: [u64; 8] = [1, 7, 11, 13, 17, 19, 23, 29];
 
+// Each segment is a run of bytes that comfortably fits in the L1 cache.
+const SEGMENT_BYTES: u64 = 32 * 1024;
 
+// The first multiple of the wheel past `u32::MAX`, where the last segment stops.
+const PRIMES_LIMIT: u64 = (u32::MAX as u64 / WHEEL + 1) * WHEEL;
 
-pub struct Primes(Vec<u64>);
+/// How far `Primes::factorise` trial divides before switching to Pollard's rho.
+pub const TRIAL_DIVISION_BOUND: u64 = 1 << 12;
+
+// A growable cache of the primes, in order.
+//
+// The cache is extended by a segmented sieve of Eratosthenes, one block at a time, as iterators run past its end.
+// Each block only needs the primes up to its square root to sieve with, and those are already in the cache.
+//
+// The cache sits behind a lock, so it can be shared between threads and between any number of iterators,
+// all of which only need a shared reference.
+// Readers only ever see whole segments, as a segment is sieved and appended while holding the write lock.
+// `Primes::global()` is a process-wide instance, for when there's no need to manage one.
+//
+// The primes are stored as `u32`s, which halves the size of the cache, and covers any range it's practical to sieve.
+// Sieving past `u32::MAX` panics.
+pub struct Primes {
+    cache: RwLock<PrimesCache>,
+}
+
+struct PrimesCache {
+    ps: Vec<u32>,
+    // every prime below this is in `ps`, and it is always a multiple of the wheel
+    sieved_to: u64,
+}
 
 impl Primes {
-    pub fn iter<'a>(&'a mut self) -> PrimesIterator<'a> {
-        PrimesIterator { ps: &mut self.0, state: PIState::I(0) }
+    // The shared cache, created the first time it is asked for.
+    pub fn global() -> &'static Primes {
+        static GLOBAL: OnceLock<Primes> = OnceLock::new();
+        GLOBAL.get_or_init(Primes::default)
+    }
+
+    // A cache that already holds all the primes up to and including `n`.
+    //
+    // Panics if `n` is past `u32::MAX`.
+    pub fn up_to(n: u64) -> Primes {
+        let primes = Primes::with_capacity_up_to(n);
+        primes.ensure_sieved(n);
+        primes
     }
 
-    pub fn factorise<'a>(&'a mut self, n: u64) -> Factorisation {
+    // An empty cache with enough space reserved for the primes up to `n`, so that it doesn't need to reallocate as it grows to there.
+    //
+    // This uses the bound `pi(n) < 1.26 n / ln n` on the count of primes up to `n`.
+    pub fn with_capacity_up_to(n: u64) -> Primes {
+        let primes = Primes::default();
+        if n > 1 {
+            let bound = 1.26 * n as f64 / (n as f64).ln();
+            primes.write().ps.reserve(bound as usize);
+        }
+        primes
+    }
+
+    pub fn iter(&self) -> PrimesIterator<'_> {
+        PrimesIterator { primes: self, i: 0 }
+    }
+
+    // The primes as they are stored, without widening them to `u64`s.
+    pub fn iter_u32(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..).map(move |i| self.get(i))
+    }
+
+    // Factorise `n`, trial dividing by the primes up to `TRIAL_DIVISION_BOUND`.
+    pub fn factorise(&self, n: u64) -> Factorisation {
+        self.factorise_with_trial_bound(n, TRIAL_DIVISION_BOUND)
+    }
+
+    // Factorise `n`, trial dividing by the primes up to `bound`, and then splitting whatever is left with Pollard's rho.
+    //
+    // Trial division stops early once the next prime squared is more than what remains, as that must then be 1 or a prime.
+    // The cache only ever grows as far as the primes needed for trial division, so a large prime factor is cheap.
+    // Anything left after trial division is checked with Miller-Rabin, and split with Pollard-Brent rho if it is composite.
+    //
+    // The factors are in increasing order of prime, however they were found.
+    pub fn factorise_with_trial_bound(&self, n: u64, bound: u64) -> Factorisation {
         let mut m = n;
-        let mut ps = self.iter();
-    
-        Factorisation {
-            n,
-            factors: Factors(
-                std::iter::from_fn(move || {
-                    loop {
-                        let pr = ps.next().unwrap();
-                        if pr > m { return None }
-                        let mut po = 0;
-                        loop {
-                            if m % pr != 0 {
-                                break;
-                            }
-                            po += 1;
-                            m /= pr;
-                        }
-                        if po > 0 {
-                            return Some(Factor{ prime: pr, power: po })
-                        }
-                    }
-                }).collect()
-            )
+        let mut factors = Vec::new();
+
+        if m > 1 {
+            for pr in self.iter() {
+                if pr > bound || pr * pr > m {
+                    break;
+                }
+                let mut po = 0;
+                while m % pr == 0 {
+                    po += 1;
+                    m /= pr;
+                }
+                if po > 0 {
+                    factors.push(Factor { prime: pr, power: po });
+                }
+            }
         }
+
+        if m > 1 {
+            let mut large = Vec::new();
+            split_rho(m, &mut large);
+            large.sort_unstable();
+            for pr in large {
+                match factors.last_mut() {
+                    Some(f) if f.prime == pr => f.power += 1,
+                    _ => factors.push(Factor { prime: pr, power: 1 }),
+                }
+            }
+        }
+
+        Factorisation { n, factors: Factors(factors) }
     }
-}
 
-impl Default for Primes {
-    fn default() -> Self {
-        let mut ps = Vec::new();
-        ps.push(2);
-        Primes(ps)
+    // The i'th prime, counting 2 as the 0th, sieving more if needed.
+    fn get(&self, i: usize) -> u32 {
+        if let Some(&p) = self.read().ps.get(i) {
+            return p;
+        }
+
+        // another thread may have extended the cache while we waited for the write lock, so check again
+        let mut cache = self.write();
+        while i >= cache.ps.len() {
+            cache.extend();
+        }
+        cache.ps[i]
+    }
+
+    fn ensure_sieved(&self, n: u64) {
+        let mut cache = self.write();
+        while cache.sieved_to <= n {
+            cache.extend();
+        }
+    }
+
+    // The cache is append-only and a segment is always fully written before the lock is released,
+    // so the contents are still consistent even if another thread panicked while holding the lock.
+    fn read(&self) -> RwLockReadGuard<'_, PrimesCache> {
+        self.cache.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, PrimesCache> {
+        self.cache.write().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
-pub struct PrimesIterator<'a> {
-    ps: &'a mut Vec<u64>,
-    state: PIState,
+impl PrimesCache {
+    // Sieve the next segment, adding its primes to the cache.
+    fn extend(&mut self) {
+        let lo = self.sieved_to;
+        assert!(lo <= u64::from(u32::MAX), "prime cache can't grow past u32::MAX");
+        // the segment can't go past lo^2, or it would need sieving primes that we haven't found yet
+        let hi = (lo + SEGMENT_BYTES * WHEEL)
+            .min(lo.saturating_mul(lo) / WHEEL * WHEEL)
+            .min(PRIMES_LIMIT);
+        let mut segment = vec![0u8; ((hi - lo) / WHEEL) as usize];
+
+        // 2, 3 and 5 are taken care of by the wheel
+        for p in self.ps.iter().skip(3).map(|&p| u64::from(p)).take_while(|&p| p * p < hi) {
+            cross_off(&mut segment, lo, hi, p);
+        }
+
+        let mut found = Vec::new();
+        for (i, &byte) in segment.iter().enumerate() {
+            for (bit, &r) in WHEEL_RESIDUES.iter().enumerate() {
+                let p = lo + i as u64 * WHEEL + r;
+                if byte & (1 << bit) == 0 && p <= u64::from(u32::MAX) {
+                    found.push(p as u32);
+                }
+            }
+        }
+        self.ps.extend(found);
+        self.sieved_to = hi;
+    }
 }
 
-enum PIState {
-    I(usize),
-    P(u64)
+// Push the prime factors of `n`, with repeats and in no particular order.
+fn split_rho(n: u64, out: &mut Vec<u64>) {
+    if n == 1 {
+        return;
+    }
+    if is_prime(n) {
+        out.push(n);
+        return;
+    }
+    let d = brent_rho(n);
+    split_rho(d, out);
+    split_rho(n / d, out);
 }
 
-impl <'a> Iterator for PrimesIterator<'a> {
-    type Item=u64;
+// A non-trivial factor of the composite `n`, by Brent's variant of Pollard's rho.
+//
+// This walks `x -> x^2 + c mod n`, which falls into a cycle modulo each prime factor `p` after around sqrt(p) steps.
+// The cycle is found with Brent's power-of-two stride rather than Floyd's tortoise and hare,
+// and the differences are multiplied together so that there is only one gcd per batch.
+// If a batch overshoots and the gcd is `n` itself, it is replayed one step at a time.
+// Should that also only find `n`, the walk is retried with the next `c`.
+fn brent_rho(n: u64) -> u64 {
+    const BATCH: u64 = 128;
 
-    fn next(&mut self) -> Option<u64> {
-        match self.state {
-            PIState::I(ref mut i) if *i < self.ps.len() => {
-                let p = self.ps[*i];
-                *i+=1;
-                Some(p)
+    if n % 2 == 0 {
+        return 2;
+    }
+
+    let n128 = u128::from(n);
+    for c in 1..n {
+        let f = |x: u64| ((u128::from(mod_mul(x, x, n)) + u128::from(c)) % n128) as u64;
+
+        let mut y = 2;
+        let mut x = y;
+        let mut ys = y;
+        let mut q = 1;
+        let mut g = 1;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
             }
-            PIState::I(_) => {
-                let pi = self.ps[self.ps.len()-1] + 1;
-                self.state = PIState::P(pi);
-                self.next()
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(y);
+                    q = mod_mul(q, x.abs_diff(y), n);
+                }
+                g = gcd(q, n);
+                k += BATCH;
             }
-            PIState::P(ref mut pi) => {
-                let mut i = *pi;
-                // we trade one square root op here vs needing to compute i*i repeatedly within the loop
-                let i_root = (i as f64).sqrt().ceil() as u64;
-                loop {
-                    if self.ps.iter()
-                        .take_while(|&&p| p <= i_root)
-                        .any(|&p| i % p == 0) // rely upon this short-circuting
-                    {
-                        i += 1;
-                        continue;
-                    } else {
-                        *pi = i+1;
-                        self.ps.push(i);
-                        break Some(i);
-                    }
+            r *= 2;
+        }
+
+        if g == n {
+            loop {
+                ys = f(ys);
+                g = gcd(x.abs_diff(ys), n);
+                if g > 1 {
+                    break;
                 }
             }
         }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!("no factor found for composite {}", n)
+}
+
+// Mark the multiples of `p` in the segment `lo..hi` as composite.
+//
+// Only multiples `p*k` with `k` coprime to the wheel land on a bit.
+// For each of the 8 residues of `k`, those multiples form an arithmetic progression with step `30p`,
+// and they all share a single residue mod 30, so a single bit in each byte.
+// We start from `p*p`, as any smaller multiple has a smaller prime factor that will already have crossed it off.
+fn cross_off(segment: &mut [u8], lo: u64, hi: u64, p: u64) {
+    let k_min = p.max(lo.div_ceil(p));
+    let step = WHEEL * p;
+    for &r in WHEEL_RESIDUES.iter() {
+        // the first k at least k_min with k = r mod 30
+        let k = k_min + (r + WHEEL - k_min % WHEEL) % WHEEL;
+        let mut m = p * k;
+        let bit = WHEEL_RESIDUES.iter().position(|&w| w == m % WHEEL).unwrap();
+        while m < hi {
+            segment[((m - lo) / WHEEL) as usize] |= 1 << bit;
+            m += step;
+        }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Primes {
+            cache: RwLock::new(PrimesCache {
+                ps: vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29],
+                sieved_to: WHEEL,
+            }),
+        }
+    }
+}
+
+pub struct PrimesIterator<'a> {
+    primes: &'a Primes,
+    i: usize,
+}
+
+impl Iterator for PrimesIterator<'_> {
+    type Item=u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let p = self.primes.get(self.i);
+        self.i += 1;
+        Some(u64::from(p))
     }
 }
 
 
 
-pub fn is_palendrome(n: u64) -> bool {
+pub fn is_palendrome<T: Natural>(n: T) -> bool {
     let forwd = format!("{}", n);
     let bakwd = forwd.chars().rev().collect::<String>();
     forwd.chars().zip(bakwd.chars()).all(|(f, b)| f == b)
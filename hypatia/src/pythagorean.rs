@@ -0,0 +1,179 @@
+use crate::modular::gcd;
+
+// A pythagorean triple
+//
+// a,b,c where:
+//  a^2 + b^2 = c^2
+//
+// and 0 < a < b < c.
+// (a and b can never be equal, as sqrt(2) is irrational.)
+//
+// A triple is primitive if a, b and c have no common factor, and every triple is a multiple of exactly one primitive triple.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Triple {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Triple {
+    // The triple from Euclid's formula, for `m > n > 0`:
+    //
+    // a = m^2 - n^2
+    // b = 2mn
+    // c = m^2 + n^2
+    //
+    // with a and b swapped if need be to keep a < b.
+    // This is primitive exactly when m and n are coprime and not both odd,
+    // and every primitive triple comes from just one such pair.
+    //
+    // Panics unless `m > n > 0`.
+    pub fn euclid(m: u64, n: u64) -> Triple {
+        assert!(m > n && n > 0, "Euclid's formula needs m > n > 0, but got m = {}, n = {}", m, n);
+        let (a, b) = (m * m - n * n, 2 * m * n);
+        Triple { a: a.min(b), b: a.max(b), c: m * m + n * n }
+    }
+
+    pub fn perimeter(&self) -> u64 {
+        self.a + self.b + self.c
+    }
+
+    pub fn product(&self) -> u64 {
+        self.a * self.b * self.c
+    }
+
+    pub fn is_primitive(&self) -> bool {
+        gcd(gcd(self.a, self.b), self.c) == 1
+    }
+
+    // This triple with each side multiplied by `k`.
+    pub fn scale(&self, k: u64) -> Triple {
+        Triple { a: k * self.a, b: k * self.b, c: k * self.c }
+    }
+
+    // The three children of this triple in the Berggren tree.
+    //
+    // Multiplying (a, b, c) by each of the three matrices
+    //
+    //  | 1 -2  2 |   | 1  2  2 |   | -1  2  2 |
+    //  | 2 -1  2 |   | 2  1  2 |   | -2  1  2 |
+    //  | 2 -2  3 |   | 2  2  3 |   | -2  2  3 |
+    //
+    // gives three more primitive triples, each with a longer hypotenuse and perimeter.
+    // Starting from (3, 4, 5), this reaches every primitive triple exactly once.
+    pub fn children(&self) -> [Triple; 3] {
+        let (a, b, c) = (self.a as i64, self.b as i64, self.c as i64);
+        let triple = |a: i64, b: i64, c: i64| {
+            let (a, b) = (a.unsigned_abs(), b.unsigned_abs());
+            Triple { a: a.min(b), b: a.max(b), c: c as u64 }
+        };
+        [
+            triple(a - 2 * b + 2 * c, 2 * a - b + 2 * c, 2 * a - 2 * b + 3 * c),
+            triple(a + 2 * b + 2 * c, 2 * a + b + 2 * c, 2 * a + 2 * b + 3 * c),
+            triple(-a + 2 * b + 2 * c, -2 * a + b + 2 * c, -2 * a + 2 * b + 3 * c),
+        ]
+    }
+}
+
+impl std::fmt::Display for Triple {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {}, {})", self.a, self.b, self.c)
+    }
+}
+
+// How far to generate triples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    // a + b + c <= n
+    Perimeter(u64),
+    // c <= n
+    Hypotenuse(u64),
+}
+
+impl Limit {
+    pub fn admits(&self, t: &Triple) -> bool {
+        match *self {
+            Limit::Perimeter(n) => t.perimeter() <= n,
+            Limit::Hypotenuse(n) => t.c <= n,
+        }
+    }
+}
+
+// The primitive triples within the limit, by a depth-first walk of the Berggren tree.
+//
+// Both the perimeter and the hypotenuse grow going down the tree, so a branch can be dropped as soon as it passes the limit.
+// The triples come out in no particular order.
+pub fn primitive_triples(limit: Limit) -> impl Iterator<Item = Triple> {
+    let root = Triple { a: 3, b: 4, c: 5 };
+    let mut stack = if limit.admits(&root) { vec![root] } else { Vec::new() };
+    std::iter::from_fn(move || {
+        let t = stack.pop()?;
+        stack.extend(t.children().iter().filter(|c| limit.admits(c)));
+        Some(t)
+    })
+}
+
+// The primitive triples within the limit, from Euclid's formula.
+//
+// These come out in order of `m` and then `n`.
+// The smallest triple for each `m` is at `n = 1`, with perimeter `2m(m + 1)` and hypotenuse `m^2 + 1`,
+// so `m` stops once that is past the limit.
+pub fn euclid_triples(limit: Limit) -> impl Iterator<Item = Triple> {
+    (2..)
+        .take_while(move |&m| limit.admits(&Triple::euclid(m, 1)))
+        .flat_map(move |m| {
+            (1..m)
+                .filter(move |&n| (m - n) % 2 == 1 && gcd(m, n) == 1)
+                .map(move |n| Triple::euclid(m, n))
+                .filter(move |t| limit.admits(t))
+        })
+}
+
+// Every triple within the limit, primitive or not.
+//
+// Each primitive triple is followed by its multiples.
+pub fn triples(limit: Limit) -> impl Iterator<Item = Triple> {
+    primitive_triples(limit).flat_map(move |t| {
+        (1..).map(move |k| t.scale(k)).take_while(move |t| limit.admits(t))
+    })
+}
+
+// Every triple with `a + b + c = perimeter`, in order of increasing `a`.
+//
+// The triple `k * euclid(m, n)` has perimeter `2km(m + n)`, so this looks for `m` and `n` with `m(m + n)` dividing `perimeter/2`.
+// Only even perimeters have any triples.
+pub fn triples_with_perimeter(perimeter: u64) -> Vec<Triple> {
+    let mut ts = Vec::new();
+    if perimeter % 2 == 1 {
+        return ts;
+    }
+
+    let half = perimeter / 2;
+    for m in (2..).take_while(|&m| m * (m + 1) <= half) {
+        if half % m != 0 {
+            continue;
+        }
+        for n in 1..m {
+            let s = m * (m + n);
+            if half % s == 0 && (m - n) % 2 == 1 && gcd(m, n) == 1 {
+                ts.push(Triple::euclid(m, n).scale(half / s));
+            }
+        }
+    }
+    ts.sort();
+    ts
+}
+
+// The number of triples with each perimeter, for perimeters `0 ..= limit`.
+//
+// This counts every triple, primitive or not, by adding each multiple of each primitive perimeter.
+pub fn perimeter_counts(limit: u64) -> Vec<u32> {
+    let mut counts = vec![0u32; limit as usize + 1];
+    for t in primitive_triples(Limit::Perimeter(limit)) {
+        let p = t.perimeter() as usize;
+        for q in (p..counts.len()).step_by(p) {
+            counts[q] += 1;
+        }
+    }
+    counts
+}
@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::decimal::{forward_binop, Decimal, ParseDecimalError};
+use crate::expansion::DecimalExpansion;
+
+// An exact fraction of two big integers.
+//
+// It is always kept in lowest terms, with a positive denominator, so that each value has exactly one representation.
+// That means equality and hashing can work directly on the numerator and denominator.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: Decimal,
+    denom: Decimal,
+}
+
+impl Rational {
+    // The fraction `numer/denom`, reduced to lowest terms.
+    //
+    // Panics if `denom` is zero.
+    pub fn new(numer: Decimal, denom: Decimal) -> Rational {
+        assert!(!denom.is_zero(), "rational with a zero denominator");
+
+        let g = numer.gcd(&denom);
+        let (numer, denom) = if g == Decimal::from(1u32) {
+            (numer, denom)
+        } else {
+            (&numer / &g, &denom / &g)
+        };
+
+        if denom.is_negative() {
+            Rational { numer: -numer, denom: -denom }
+        } else {
+            Rational { numer, denom }
+        }
+    }
+
+    // A fraction of machine integers.
+    pub fn from_fraction(numer: i64, denom: i64) -> Rational {
+        Rational::new(Decimal::from(numer), Decimal::from(denom))
+    }
+
+    pub fn zero() -> Rational {
+        Rational::from(Decimal::zero())
+    }
+
+    pub fn one() -> Rational {
+        Rational::from(Decimal::from(1u32))
+    }
+
+    pub fn numer(&self) -> &Decimal {
+        &self.numer
+    }
+
+    // The denominator, which is always positive.
+    pub fn denom(&self) -> &Decimal {
+        &self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == Decimal::from(1u32)
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational { numer: self.numer.abs(), denom: self.denom.clone() }
+    }
+
+    // The reciprocal, `denom/numer`.
+    //
+    // Panics if this is zero.
+    pub fn recip(&self) -> Rational {
+        Rational::new(self.denom.clone(), self.numer.clone())
+    }
+
+    // Raise to an integer power, which may be negative.
+    //
+    // As the numerator and denominator are coprime, so are their powers, and there is no need to reduce again.
+    pub fn pow(&self, exp: i32) -> Rational {
+        let r = if exp < 0 { self.recip() } else { self.clone() };
+        let e = exp.unsigned_abs();
+        Rational { numer: r.numer.pow(e), denom: r.denom.pow(e) }
+    }
+
+    // The largest integer no larger than this.
+    pub fn floor(&self) -> Decimal {
+        let (q, r) = self.numer.div_rem(&self.denom);
+        if r.is_negative() { &q - &Decimal::from(1u32) } else { q }
+    }
+
+    // The integer part, rounding towards zero.
+    pub fn trunc(&self) -> Decimal {
+        &self.numer / &self.denom
+    }
+
+    // The decimal expansion, with its recurring cycle.
+    pub fn expansion(&self) -> DecimalExpansion {
+        DecimalExpansion::new(&self.numer, &self.denom)
+    }
+}
+
+impl From<Decimal> for Rational {
+    fn from(n: Decimal) -> Rational {
+        Rational { numer: n, denom: Decimal::from(1u32) }
+    }
+}
+
+impl From<&Decimal> for Rational {
+    fn from(n: &Decimal) -> Rational {
+        Rational::from(n.clone())
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Rational {
+        Rational::from(Decimal::from(n))
+    }
+}
+
+impl Add for &Rational {
+    type Output = Rational;
+
+    // a/b + c/d = (ad + cb)/bd
+    fn add(self, rhs: &Rational) -> Rational {
+        Rational::new(
+            &(&self.numer * &rhs.denom) + &(&rhs.numer * &self.denom),
+            &self.denom * &rhs.denom)
+    }
+}
+
+impl Sub for &Rational {
+    type Output = Rational;
+
+    // a/b - c/d = (ad - cb)/bd
+    fn sub(self, rhs: &Rational) -> Rational {
+        Rational::new(
+            &(&self.numer * &rhs.denom) - &(&rhs.numer * &self.denom),
+            &self.denom * &rhs.denom)
+    }
+}
+
+impl Mul for &Rational {
+    type Output = Rational;
+
+    // Cross-cancelling first keeps the intermediate products small, and leaves the result already in lowest terms.
+    fn mul(self, rhs: &Rational) -> Rational {
+        let g1 = self.numer.gcd(&rhs.denom);
+        let g2 = rhs.numer.gcd(&self.denom);
+        Rational {
+            numer: &(&self.numer / &g1) * &(&rhs.numer / &g2),
+            denom: &(&self.denom / &g2) * &(&rhs.denom / &g1),
+        }
+    }
+}
+
+impl Div for &Rational {
+    type Output = Rational;
+
+    // Multiply by the reciprocal.
+    //
+    // Panics if `rhs` is zero.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: &Rational) -> Rational {
+        self * &rhs.recip()
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational { numer: -&self.numer, denom: self.denom.clone() }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational { numer: -self.numer, denom: self.denom }
+    }
+}
+
+forward_binop!(Rational, Add, add, AddAssign, add_assign);
+forward_binop!(Rational, Sub, sub, SubAssign, sub_assign);
+forward_binop!(Rational, Mul, mul, MulAssign, mul_assign);
+forward_binop!(Rational, Div, div, DivAssign, div_assign);
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    // As both denominators are positive, a/b < c/d exactly when ad < cb.
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl std::iter::Sum for Rational {
+    fn sum<I: Iterator<Item = Rational>>(iter: I) -> Rational {
+        iter.fold(Rational::zero(), |l, r| &l + &r)
+    }
+}
+
+impl<'a> std::iter::Sum<&'a Rational> for Rational {
+    fn sum<I: Iterator<Item = &'a Rational>>(iter: I) -> Rational {
+        iter.fold(Rational::zero(), |l, r| &l + r)
+    }
+}
+
+impl std::iter::Product for Rational {
+    fn product<I: Iterator<Item = Rational>>(iter: I) -> Rational {
+        iter.fold(Rational::one(), |l, r| &l * &r)
+    }
+}
+
+impl<'a> std::iter::Product<&'a Rational> for Rational {
+    fn product<I: Iterator<Item = &'a Rational>>(iter: I) -> Rational {
+        iter.fold(Rational::one(), |l, r| &l * r)
+    }
+}
+
+// Printed as `numer/denom`, or just `numer` for whole numbers.
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+// Why a string could not be parsed as a `Rational`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRationalError {
+    Numerator(ParseDecimalError),
+    Denominator(ParseDecimalError),
+    ZeroDenominator,
+}
+
+impl std::fmt::Display for ParseRationalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseRationalError::Numerator(e) => write!(f, "invalid numerator: {}", e),
+            ParseRationalError::Denominator(e) => write!(f, "invalid denominator: {}", e),
+            ParseRationalError::ZeroDenominator => write!(f, "rational with a zero denominator"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRationalError {}
+
+impl std::str::FromStr for Rational {
+    type Err = ParseRationalError;
+
+    // Parse either `numer/denom` or a plain integer.
+    //
+    // Each side is parsed as a `Decimal`, so may be signed and have whitespace around it.
+    // The positions in any digit error are relative to that side.
+    fn from_str(s: &str) -> Result<Rational, ParseRationalError> {
+        let (numer, denom) = match s.find('/') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let numer: Decimal = numer.parse().map_err(ParseRationalError::Numerator)?;
+        let denom: Decimal = match denom {
+            Some(d) => d.parse().map_err(ParseRationalError::Denominator)?,
+            None => Decimal::from(1u32),
+        };
+        if denom.is_zero() {
+            return Err(ParseRationalError::ZeroDenominator);
+        }
+
+        Ok(Rational::new(numer, denom))
+    }
+}
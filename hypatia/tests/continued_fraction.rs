@@ -0,0 +1,110 @@
+use std::convert::TryFrom;
+
+use hypatia::continued_fraction::{convergents, e_terms, ContinuedFraction, Pell};
+use hypatia::decimal::Decimal;
+use hypatia::rational::Rational;
+
+#[test]
+fn square_root_expansions() {
+    let root23 = ContinuedFraction::sqrt(23);
+    assert_eq!(root23.prefix(), &[4]);
+    assert_eq!(root23.period(), &[1, 3, 1, 8]);
+    assert_eq!(root23.to_string(), "[4; (1, 3, 1, 8)]");
+    assert_eq!(root23.terms().take(7).collect::<Vec<_>>(), vec![4, 1, 3, 1, 8, 1, 3]);
+
+    assert_eq!(ContinuedFraction::sqrt(2).to_string(), "[1; (2)]");
+    assert_eq!(ContinuedFraction::sqrt(13).period(), &[1, 1, 1, 1, 6]);
+
+    let root16 = ContinuedFraction::sqrt(16);
+    assert!(!root16.is_periodic());
+    assert_eq!(root16.to_string(), "[4]");
+    assert_eq!(root16.terms().collect::<Vec<_>>(), vec![4]);
+
+    // Problem 64
+    let odd = (2..=10_000)
+        .filter(|&d| ContinuedFraction::sqrt(d).period().len() % 2 == 1)
+        .count();
+    assert_eq!(odd, 1322);
+}
+
+#[test]
+fn finite_expansions() {
+    let cf = ContinuedFraction::from_fraction(415, 93);
+    assert_eq!(cf.prefix(), &[4, 2, 6, 7]);
+    assert_eq!(cf.to_string(), "[4; 2, 6, 7]");
+    assert_eq!(cf.to_rational(), Some(Rational::from_fraction(415, 93)));
+    assert_eq!(ContinuedFraction::sqrt(7).to_rational(), None);
+
+    let mixed = ContinuedFraction::new(vec![1, 2], vec![3, 4]);
+    assert_eq!(mixed.to_string(), "[1; 2, (3, 4)]");
+    assert_eq!(mixed.terms().take(6).collect::<Vec<_>>(), vec![1, 2, 3, 4, 3, 4]);
+}
+
+#[test]
+fn convergent_sequences() {
+    let root2: Vec<_> = ContinuedFraction::sqrt(2)
+        .convergents()
+        .take(4)
+        .map(|(p, q)| (u64::try_from(&p).unwrap(), u64::try_from(&q).unwrap()))
+        .collect();
+    assert_eq!(root2, vec![(1, 1), (3, 2), (7, 5), (17, 12)]);
+
+    // Problem 57
+    let heavy = ContinuedFraction::sqrt(2)
+        .convergents()
+        .skip(1)
+        .take(1000)
+        .filter(|(p, q)| p.digits().len() > q.digits().len())
+        .count();
+    assert_eq!(heavy, 153);
+
+    // Problem 65
+    assert_eq!(e_terms().take(10).collect::<Vec<_>>(), vec![2, 1, 2, 1, 1, 4, 1, 1, 6, 1]);
+    let (p, _) = convergents(e_terms()).nth(99).unwrap();
+    assert_eq!(p.digits().iter().map(|&d| d as u32).sum::<u32>(), 272);
+}
+
+fn pell_value(d: u64, (x, y): &(Decimal, Decimal)) -> Rational {
+    let x2 = Rational::from(x * x);
+    let dy2 = Rational::from(&(&Decimal::from(d) * y) * y);
+    &x2 - &dy2
+}
+
+#[test]
+fn pell_equations() {
+    assert!(Pell::new(25).is_none());
+
+    let pell7 = Pell::new(7).unwrap();
+    assert_eq!(pell7.fundamental(), &(Decimal::from(8u32), Decimal::from(3u32)));
+    assert!(pell7.negative_fundamental().is_none());
+    assert_eq!(pell7.negative_solutions().count(), 0);
+
+    let pell2 = Pell::new(2).unwrap();
+    let negatives: Vec<_> = pell2.negative_solutions().take(3).collect();
+    let expected: Vec<_> = [(1u32, 1u32), (7, 5), (41, 29)]
+        .iter()
+        .map(|&(x, y)| (Decimal::from(x), Decimal::from(y)))
+        .collect();
+    assert_eq!(negatives, expected);
+    assert_eq!(pell2.fundamental(), &(Decimal::from(3u32), Decimal::from(2u32)));
+
+    for d in 2..200 {
+        let pell = match Pell::new(d) {
+            Some(pell) => pell,
+            None => continue,
+        };
+        for solution in pell.solutions().take(5) {
+            assert_eq!(pell_value(d, &solution), Rational::one(), "d = {}", d);
+        }
+        for solution in pell.negative_solutions().take(5) {
+            assert_eq!(pell_value(d, &solution), -Rational::one(), "d = {}", d);
+        }
+    }
+
+    // Problem 66
+    let largest = (2..=1000)
+        .filter_map(|d| Pell::new(d).map(|pell| (pell.fundamental().0.clone(), d)))
+        .max()
+        .unwrap();
+    assert_eq!(largest.1, 661);
+}
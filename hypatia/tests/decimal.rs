@@ -0,0 +1,363 @@
+use std::convert::TryFrom;
+
+use hypatia::decimal::{Decimal, ParseDecimalError};
+use num::{BigInt, BigUint, Zero};
+
+// A small linear congruential generator, so that the cases are varied but repeatable.
+fn numbers(seed: u64, count: usize) -> Vec<String> {
+    long_numbers(seed, count, 60)
+}
+
+fn long_numbers(seed: u64, count: usize, max_len: u64) -> Vec<String> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    };
+
+    (0..count).map(|_| {
+        let len = 1 + (next() % max_len) as usize;
+        let mut s: String = (0..len).map(|_| char::from(b'0' + (next() % 10) as u8)).collect();
+        // trim leading zeros, as `BigUint` would not print them
+        while s.len() > 1 && s.starts_with('0') {
+            s.remove(0);
+        }
+        s
+    }).collect()
+}
+
+#[test]
+fn mul_matches_biguint() {
+    // long enough that the karatsuba path is exercised, including lopsided operands
+    let ns = long_numbers(6, 12, 3000);
+    for a in &ns {
+        for b in &ns {
+            let product = &dec(a) * &dec(b);
+            let expected = a.parse::<BigUint>().unwrap() * b.parse::<BigUint>().unwrap();
+            assert_eq!(product.to_string(), expected.to_string());
+        }
+    }
+}
+
+#[test]
+fn long_div_rem_matches_biguint() {
+    let ns = long_numbers(7, 12, 1000);
+    for a in &ns {
+        for b in &ns {
+            let bb = b.parse::<BigUint>().unwrap();
+            if bb.is_zero() { continue; }
+            let (q, r) = dec(a).div_rem(&dec(b));
+            let ba = a.parse::<BigUint>().unwrap();
+            assert_eq!(q.to_string(), (&ba / &bb).to_string());
+            assert_eq!(r.to_string(), (&ba % &bb).to_string());
+        }
+    }
+}
+
+#[test]
+fn digits_are_little_endian() {
+    let d = dec("1000000000123");
+    assert_eq!(d.digits(), vec![3, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    assert_eq!(Decimal::from_u32(2).pow(1000).digits().iter().map(|&d| u64::from(d)).sum::<u64>(), 1366);
+}
+
+#[test]
+fn div_rem_matches_biguint() {
+    let ns = numbers(1, 40);
+    for a in &ns {
+        for b in &ns {
+            let (da, db) = (dec(a), dec(b));
+            let (ba, bb) = (a.parse::<BigUint>().unwrap(), b.parse::<BigUint>().unwrap());
+            if bb.is_zero() { continue; }
+
+            let (q, r) = da.div_rem(&db);
+            assert_eq!(q.to_string(), (&ba / &bb).to_string(), "{} / {}", a, b);
+            assert_eq!(r.to_string(), (&ba % &bb).to_string(), "{} % {}", a, b);
+            assert_eq!(&(&q * &db) + &r, da);
+        }
+    }
+}
+
+#[test]
+fn signed_div_rem_truncates_like_bigint() {
+    let ns = numbers(2, 12);
+    for a in &ns {
+        for b in &ns {
+            for (sa, sb) in [("", ""), ("-", ""), ("", "-"), ("-", "-")].iter() {
+                let (a, b) = (format!("{}{}", sa, a), format!("{}{}", sb, b));
+                let (ia, ib) = (a.parse::<BigInt>().unwrap(), b.parse::<BigInt>().unwrap());
+                if ib.is_zero() { continue; }
+
+                let (da, db) = (dec(&a), dec(&b));
+                assert_eq!((&da / &db).to_string(), (&ia / &ib).to_string(), "{} / {}", a, b);
+                assert_eq!((&da % &db).to_string(), (&ia % &ib).to_string(), "{} % {}", a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn signed_add_sub_and_ordering_match_bigint() {
+    // zero, and numbers either side of a limb boundary, as well as the random ones
+    let mut ns = numbers(9, 10);
+    ns.extend(["0", "1", "999999999", "1000000000", "1000000001"].iter().map(|s| s.to_string()));
+
+    for a in &ns {
+        for b in &ns {
+            // every sign combination, which includes equal magnitudes with opposite signs when a == b
+            for (sa, sb) in [("", ""), ("-", ""), ("", "-"), ("-", "-")].iter() {
+                let (a, b) = (format!("{}{}", sa, a), format!("{}{}", sb, b));
+                let (ia, ib) = (a.parse::<BigInt>().unwrap(), b.parse::<BigInt>().unwrap());
+                let (da, db) = (dec(&a), dec(&b));
+
+                assert_eq!((&da + &db).to_string(), (&ia + &ib).to_string(), "{} + {}", a, b);
+                assert_eq!((&da - &db).to_string(), (&ia - &ib).to_string(), "{} - {}", a, b);
+                assert_eq!(da.cmp(&db), ia.cmp(&ib), "{} cmp {}", a, b);
+                assert_eq!((-&da).to_string(), (-&ia).to_string(), "-{}", a);
+
+                let mut acc = da.clone();
+                acc += &db;
+                assert_eq!(acc.to_string(), (&ia + &ib).to_string(), "{} += {}", a, b);
+                acc -= db.clone();
+                assert_eq!(acc, da, "{} + {} - {}", a, b, b);
+                acc *= &db;
+                assert_eq!(acc.to_string(), (&ia * &ib).to_string(), "{} *= {}", a, b);
+                if !ib.is_zero() {
+                    let mut q = da.clone();
+                    q /= &db;
+                    assert_eq!(q.to_string(), (&ia / &ib).to_string(), "{} /= {}", a, b);
+                    let mut r = da.clone();
+                    r %= db.clone();
+                    assert_eq!(r.to_string(), (&ia % &ib).to_string(), "{} %= {}", a, b);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn equal_values_hash_equally() {
+    use std::collections::HashSet;
+
+    let five = Decimal::from(5u32);
+    let minus_five = Decimal::from(-5i64);
+    let values = vec![
+        dec("0"),
+        dec("-0"),
+        &five - &five,
+        &minus_five + &five,
+        dec("7"),
+        dec("007"),
+        &Decimal::from(12u32) - &five,
+        dec("-7"),
+        -dec("7"),
+        &minus_five - &Decimal::from(2u32),
+        dec("123456789012345678901234567890"),
+        &dec("123456789012345678901234567891") - &Decimal::from(1u32),
+    ];
+    let set: HashSet<Decimal> = values.into_iter().collect();
+    assert_eq!(set.len(), 4);
+    assert!(set.contains(&Decimal::zero()));
+    assert!(set.contains(&Decimal::from(7u32)));
+    assert!(set.contains(&Decimal::from(-7i64)));
+}
+
+#[test]
+fn div_rem_small_matches_biguint() {
+    for a in numbers(3, 40) {
+        for &d in &[1u32, 2, 7, 10, 999_999_937, u32::MAX] {
+            let (q, r) = dec(&a).div_rem_small(d);
+            let ba = a.parse::<BigUint>().unwrap();
+            assert_eq!(q.to_string(), (&ba / d).to_string());
+            assert_eq!(BigUint::from(r), &ba % d);
+        }
+    }
+}
+
+#[test]
+fn pow_matches_biguint() {
+    for a in numbers(4, 10) {
+        for e in 0..20u32 {
+            let ba = a.parse::<BigUint>().unwrap();
+            assert_eq!(dec(&a).pow(e).to_string(), ba.pow(e).to_string());
+        }
+    }
+}
+
+#[test]
+fn mod_pow_matches_biguint() {
+    let ns = numbers(5, 8);
+    for a in &ns {
+        for e in &ns {
+            for m in &ns {
+                let bm = m.parse::<BigUint>().unwrap();
+                if bm.is_zero() { continue; }
+                let expected = a.parse::<BigUint>().unwrap().modpow(&e.parse::<BigUint>().unwrap(), &bm);
+                let actual = dec(a).mod_pow(&dec(e), &dec(m));
+                assert_eq!(actual.to_string(), expected.to_string());
+            }
+        }
+    }
+
+    // the last ten digits of 28433 * 2^7830457 + 1
+    let ten_digits = Decimal::from_u32(10).pow(10);
+    let p = Decimal::from_u32(2).mod_pow(&Decimal::from_u32(7_830_457), &ten_digits);
+    let last_ten = &(&(&p * &Decimal::from_u32(28433)) + &Decimal::from_u32(1)) % &ten_digits;
+    assert_eq!(last_ten.to_string(), "8739992577");
+    assert_eq!(Decimal::from_u32(3).mod_pow(&Decimal::zero(), &Decimal::from_u32(1)), Decimal::zero());
+}
+
+fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_accepts_signs_underscores_and_whitespace() {
+    assert_eq!(dec("  -0001_000_000\n"), -dec("1000000"));
+    assert_eq!(dec("+42"), dec("42"));
+    assert_eq!(dec("-0"), Decimal::zero());
+    assert!(!dec("-000").is_negative());
+}
+
+#[test]
+fn parse_reports_the_offending_position() {
+    assert_eq!("".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+    assert_eq!(" - ".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+    assert_eq!(" 12x4".parse::<Decimal>(), Err(ParseDecimalError::InvalidDigit { position: 3, found: 'x' }));
+    assert_eq!("_1".parse::<Decimal>(), Err(ParseDecimalError::InvalidDigit { position: 0, found: '_' }));
+    assert_eq!("1 2".parse::<Decimal>(), Err(ParseDecimalError::InvalidDigit { position: 1, found: ' ' }));
+    assert_eq!("--1".parse::<Decimal>(), Err(ParseDecimalError::InvalidDigit { position: 1, found: '-' }));
+}
+
+#[test]
+fn converts_to_and_from_machine_integers() {
+    assert_eq!(Decimal::from(u64::MAX).to_string(), u64::MAX.to_string());
+    assert_eq!(Decimal::from(u128::MAX).to_string(), u128::MAX.to_string());
+    assert_eq!(Decimal::from(i64::MIN).to_string(), i64::MIN.to_string());
+    assert_eq!(Decimal::from(0u64), Decimal::zero());
+
+    assert_eq!(u64::try_from(&Decimal::from(u64::MAX)), Ok(u64::MAX));
+    assert!(u64::try_from(&(&Decimal::from(u64::MAX) + &Decimal::from(1u8))).is_err());
+    assert!(u32::try_from(&Decimal::from(-1i64)).is_err());
+    assert_eq!(i64::try_from(&Decimal::from(i64::MIN)), Ok(i64::MIN));
+    assert!(i64::try_from(&(&Decimal::from(i64::MIN) - &Decimal::from(1u8))).is_err());
+    assert!(i64::try_from(&Decimal::from(i64::MAX as u64 + 1)).is_err());
+    assert_eq!(i128::try_from(&Decimal::from(i128::MIN)), Ok(i128::MIN));
+    assert_eq!(u128::try_from(&Decimal::from(u128::MAX)), Ok(u128::MAX));
+    assert!(u128::try_from(&Decimal::from(u128::MAX).pow(2)).is_err());
+    assert_eq!(u8::try_from(&Decimal::zero()), Ok(0));
+}
+
+#[cfg(feature = "num")]
+#[test]
+fn converts_to_and_from_num_bigints() {
+    for a in long_numbers(8, 20, 200) {
+        let ba = a.parse::<BigUint>().unwrap();
+        assert_eq!(Decimal::from(&ba), dec(&a));
+        assert_eq!(BigUint::try_from(&dec(&a)), Ok(ba.clone()));
+
+        let ia = -BigInt::from(ba);
+        assert_eq!(Decimal::from(&ia), -dec(&a));
+        assert_eq!(BigInt::from(&-dec(&a)), ia);
+    }
+    assert!(BigUint::try_from(&dec("-1")).is_err());
+}
+
+#[test]
+fn zero_has_a_canonical_form() {
+    assert_eq!(Decimal::zero().to_string(), "0");
+    assert_eq!(Decimal::zero().digits(), vec![0]);
+    assert_eq!(-Decimal::zero(), Decimal::zero());
+    assert_eq!(&dec("5") - &dec("5"), dec("0"));
+    assert_eq!(format!("{:e}", Decimal::zero()), "0e0");
+}
+
+#[test]
+fn formatting_options() {
+    let d = dec("-1234567");
+    assert_eq!(format!("{}", d), "-1234567");
+    assert_eq!(format!("{:#}", d), "-1,234,567");
+    assert_eq!(format!("{:#}", dec("123456")), "123,456");
+    assert_eq!(format!("{:>10}", d), "  -1234567");
+    assert_eq!(format!("{:+}", dec("42")), "+42");
+    assert_eq!(format!("{:06}", dec("-42")), "-00042");
+
+    assert_eq!(format!("{:e}", dec("1234500")), "1.2345e6");
+    assert_eq!(format!("{:E}", dec("-7")), "-7E0");
+    assert_eq!(format!("{:.2e}", dec("1299")), "1.29e3");
+    assert_eq!(format!("{:.5e}", dec("12")), "1.20000e1");
+    assert_eq!(dec("-9876543210123").leading_digits(4), dec("9876"));
+    assert_eq!(dec("12").leading_digits(4), dec("12"));
+}
+
+#[test]
+fn sums_and_products() {
+    let ns: Vec<Decimal> = (1..=20u32).map(Decimal::from).collect();
+    assert_eq!(ns.iter().sum::<Decimal>(), dec("210"));
+    assert_eq!(ns.iter().product::<Decimal>(), dec("2432902008176640000"));
+    assert_eq!(ns.into_iter().map(|d| -d).sum::<Decimal>(), dec("-210"));
+    assert_eq!(std::iter::empty::<Decimal>().product::<Decimal>(), dec("1"));
+}
+
+#[test]
+fn radix_round_trips() {
+    for a in long_numbers(9, 20, 100) {
+        let d = dec(&a);
+        let ba = a.parse::<BigUint>().unwrap();
+        for radix in 2..=36 {
+            let digits = d.to_radix(radix);
+            assert_eq!(digits, ba.to_radix_le(radix));
+            assert_eq!(d.radix_digits(radix).collect::<Vec<_>>(), digits);
+            assert_eq!(Decimal::from_radix(&digits, radix), Some(d.clone()));
+            assert_eq!(d.to_str_radix(radix), ba.to_str_radix(radix));
+            assert_eq!(Decimal::from_str_radix(&ba.to_str_radix(radix), radix), Ok(d.clone()));
+        }
+    }
+    assert_eq!(Decimal::from_radix(&[1, 2], 2), None);
+    assert_eq!(Decimal::zero().to_radix(2), vec![0]);
+}
+
+#[test]
+fn parses_and_formats_prefixed_strings() {
+    assert_eq!(dec("0xff"), dec("255"));
+    assert_eq!(dec(" -0XDead_Beef "), dec("-3735928559"));
+    assert_eq!(dec("0o777"), dec("511"));
+    assert_eq!(dec("+0b1010"), dec("10"));
+    assert_eq!("0b102".parse::<Decimal>(), Err(ParseDecimalError::InvalidDigit { position: 4, found: '2' }));
+    assert_eq!("0x".parse::<Decimal>(), Err(ParseDecimalError::Empty));
+
+    assert_eq!(format!("{:x}", dec("255")), "ff");
+    assert_eq!(format!("{:#X}", dec("-255")), "-0xFF");
+    assert_eq!(format!("{:#b}", dec("5")), "0b101");
+    assert_eq!(format!("{:o}", dec("8")), "10");
+    assert_eq!(format!("{:#010b}", dec("5")), "0b00000101");
+}
+
+#[test]
+fn integer_roots() {
+    for a in long_numbers(10, 20, 200) {
+        let d = dec(&a);
+        let ba = a.parse::<BigUint>().unwrap();
+        assert_eq!(d.isqrt().to_string(), ba.sqrt().to_string());
+        assert_eq!(d.icbrt().to_string(), ba.cbrt().to_string());
+        assert_eq!(d.nth_root(7).to_string(), ba.nth_root(7).to_string());
+    }
+    assert_eq!(dec("-27").icbrt(), dec("-3"));
+    assert_eq!(dec("-28").icbrt(), dec("-3"));
+
+    let p = dec("123456789123456789").pow(5);
+    assert!(p.is_perfect_power());
+    assert!(!(&p + &dec("1")).is_perfect_power());
+    assert!(dec("123456789123456789").pow(2).is_perfect_square());
+    assert!(!dec("-4").is_perfect_square());
+    assert!(dec("-8").is_perfect_power());
+    assert!(!dec("-4").is_perfect_power());
+}
+
+#[test]
+fn fast_doubling_fibonacci() {
+    for (n, f) in Decimal::fib().take(300).enumerate() {
+        assert_eq!(Decimal::nth_fib(n as u64), f);
+    }
+    assert_eq!(Decimal::nth_fib(100).to_string(), "354224848179261915075");
+}
@@ -0,0 +1,64 @@
+use hypatia::decimal::Decimal;
+use hypatia::expansion::{fraction_digits, DecimalExpansion};
+use hypatia::modular::multiplicative_order;
+
+#[test]
+fn splits_prefix_and_repetend() {
+    let sixth = DecimalExpansion::from_fraction(1, 6);
+    assert_eq!(sixth.integer_part(), &Decimal::zero());
+    assert_eq!(sixth.prefix(), &[1]);
+    assert_eq!(sixth.repetend(), &[6]);
+    assert_eq!(sixth.to_string(), "0.1(6)");
+    assert_eq!(format!("{:#}", sixth), "0.16\u{0305}");
+
+    let seventh = DecimalExpansion::from_fraction(22, 7);
+    assert_eq!(seventh.to_string(), "3.(142857)");
+    assert_eq!(seventh.cycle_length(), 6);
+
+    let eighth = DecimalExpansion::from_fraction(-9, 8);
+    assert!(eighth.is_terminating());
+    assert_eq!(eighth.to_string(), "-1.125");
+    assert_eq!(eighth.to_places(5), "-1.12500");
+
+    assert_eq!(DecimalExpansion::from_fraction(4, -2).to_string(), "-2");
+    assert_eq!(DecimalExpansion::from_fraction(0, -3).to_string(), "0");
+    assert_eq!(DecimalExpansion::from_fraction(7, 12).to_string(), "0.58(3)");
+    assert_eq!(DecimalExpansion::from_fraction(1, 81).to_places(12), "0.012345679012");
+}
+
+#[test]
+fn longest_cycle_below_1000() {
+    // d < 1000 for which 1/d has the longest recurring cycle
+    let (d, len) = (2..1000)
+        .map(|d| (d, DecimalExpansion::from_fraction(1, d).cycle_length()))
+        .max_by_key(|&(_, len)| len)
+        .unwrap();
+    assert_eq!((d, len), (983, 982));
+
+    // the cycle length is the order of 10 modulo d, once the 2s and 5s are taken out
+    for d in 2..1000u64 {
+        let mut m = d;
+        while m % 2 == 0 { m /= 2; }
+        while m % 5 == 0 { m /= 5; }
+        let order = if m == 1 { 0 } else { multiplicative_order(10, m).unwrap() };
+        assert_eq!(DecimalExpansion::from_fraction(1, d as i64).cycle_length() as u64, order, "{}", d);
+    }
+}
+
+#[test]
+fn digit_stream_matches_long_division() {
+    let big: Decimal = "123456789012345678901234567890".parse().unwrap();
+    let q: Decimal = "9973".parse().unwrap();
+    let e = DecimalExpansion::new(&big, &q);
+
+    let scale = Decimal::from(10u32).pow(40);
+    let expected = (&(&big * &scale) / &q).to_string();
+    let actual = e.to_places(40).replace('.', "");
+    assert_eq!(actual, expected);
+
+    // a denominator far too large to find the cycle of
+    let q: Decimal = "98765432109876543210987".parse().unwrap();
+    let streamed: String = fraction_digits(&big, &q).take(40).map(|d| char::from(b'0' + d)).collect();
+    let expected = (&(&(&big % &q) * &scale) / &q).to_string();
+    assert_eq!(streamed, format!("{:0>40}", expected));
+}
@@ -0,0 +1,269 @@
+use hypatia::modular::*;
+use hypatia::numbers::Primes;
+
+// a small linear congruential generator, so the tests are repeatable without pulling in a random number crate
+fn numbers(seed: u64) -> impl Iterator<Item = u64> {
+    let mut x = seed;
+    std::iter::from_fn(move || {
+        x = x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        Some(x)
+    })
+}
+
+fn euclid(a: u64, b: u64) -> u64 {
+    if b == 0 { a } else { euclid(b, a % b) }
+}
+
+#[test]
+fn gcds() {
+    assert_eq!(gcd(0, 0), 0);
+    assert_eq!(gcd(0, 12), 12);
+    assert_eq!(gcd(12, 18), 6);
+    assert_eq!(gcd(u64::MAX, u64::MAX - 1), 1);
+    assert_eq!(gcd(1 << 63, 1 << 40), 1 << 40);
+    assert_eq!(lcm(4, 6), Some(12));
+    assert_eq!(lcm(0, 6), Some(0));
+    assert_eq!(lcm(u64::MAX, 2), None);
+
+    let ns: Vec<u64> = numbers(1).take(2000).collect();
+    for pair in ns.chunks(2) {
+        // shifting makes common factors likely
+        let (a, b) = (pair[0] >> (pair[1] % 40), pair[1] >> (pair[0] % 40));
+        let g = gcd(a, b);
+        assert_eq!(g, euclid(a, b));
+
+        let (g2, x, y) = extended_gcd(a, b);
+        assert_eq!(g2, g);
+        assert_eq!(i128::from(a) * x + i128::from(b) * y, i128::from(g));
+    }
+}
+
+#[test]
+fn inverses_and_powers() {
+    assert_eq!(mod_inverse(3, 7), Some(5));
+    assert_eq!(mod_inverse(4, 8), None);
+    assert_eq!(mod_inverse(10, 1), Some(0));
+
+    let p = u64::MAX - 58;
+    for a in numbers(2).take(200) {
+        let inv = mod_inverse(a, p).unwrap();
+        assert_eq!(mod_mul(a % p, inv, p), 1);
+        // Fermat's little theorem
+        assert_eq!(mod_pow(a, p - 1, p), 1);
+        assert_eq!(mod_pow(a, p - 2, p), inv);
+    }
+
+    assert_eq!(mod_pow(2, 10, 1000), 24);
+    assert_eq!(mod_pow(5, 0, 1), 0);
+    assert_eq!(mod_mul(u64::MAX - 1, u64::MAX - 1, u64::MAX), 1);
+
+    // the Mersenne prime 2^127 - 1, where the products no longer fit in a u128
+    let p = (1u128 << 127) - 1;
+    assert_eq!(mod_mul_u128(u128::MAX, u128::MAX, u128::MAX - 1), 1);
+    assert_eq!(mod_mul_u128(1 << 64, 1 << 64, p), 2);
+    for a in numbers(3).take(50).map(|a| u128::from(a) << 60) {
+        assert_eq!(mod_pow_u128(a, p - 1, p), 1);
+    }
+}
+
+#[test]
+fn chinese_remainders() {
+    assert_eq!(crt(&[]), Some((0, 1)));
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    // non-coprime moduli
+    assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[(7, 10), (17, 20), (7, 30)]), Some((37, 60)));
+    // residues larger than their moduli
+    assert_eq!(crt(&[(11, 3), (13, 5)]), Some((8, 15)));
+    assert_eq!(crt(&[(0, u64::MAX), (1, 2)]), None);
+
+    // an lcm that only just fits
+    let p = 4_294_967_291;
+    let q = 4_294_967_279;
+    let (x, m) = crt(&[(12_345, p), (4_294_967_000, q)]).unwrap();
+    assert_eq!(m, p * q);
+    assert_eq!((x % p, x % q), (12_345, 4_294_967_000));
+    assert_eq!(crt(&[(12_345, u64::MAX - 58), (678, q)]), None);
+}
+
+#[test]
+fn mod_newtype() {
+    type M7 = Mod<7>;
+    let a = M7::new(5);
+    let b = M7::from(12);
+    assert_eq!(b.value(), 5);
+    assert_eq!((a + b).value(), 3);
+    assert_eq!((a - M7::new(6)).value(), 6);
+    assert_eq!((a * b).value(), 4);
+    assert_eq!((a / M7::new(3)).value(), 4);
+    assert_eq!((-a).value(), 2);
+    assert_eq!((-M7::new(0)).value(), 0);
+    assert_eq!(a.pow(6).value(), 1);
+    assert_eq!(M7::new(0).inverse(), None);
+
+    let mut c = a;
+    c += b;
+    c *= b;
+    c -= a;
+    c /= b;
+    assert_eq!(c.value(), 2);
+
+    const P: u64 = 1_000_000_007;
+    let fact: Mod<P> = (1..=100_000).map(Mod::new).product();
+    assert_eq!(fact.value(), 457_992_974);
+    let sum: Mod<P> = (1..=1_000_000).map(Mod::new).sum();
+    assert_eq!(sum.value(), (500_000_500_000u64 % P));
+    assert_eq!(format!("{}", Mod::<{ u64::MAX }>::new(u64::MAX - 1) + Mod::new(5)), "4");
+}
+
+fn brute_order(a: u64, n: u64) -> Option<u64> {
+    let mut x = a % n;
+    for k in 1..=n {
+        if x == 1 % n {
+            return Some(k);
+        }
+        x = x * a % n;
+    }
+    None
+}
+
+#[test]
+fn orders_and_roots() {
+    for n in 1..300 {
+        let lambda = carmichael_lambda(n);
+        for a in 0..n {
+            let order = multiplicative_order(a, n);
+            assert_eq!(order, brute_order(a, n), "{} mod {}", a, n);
+            if let Some(k) = order {
+                assert!(lambda % k == 0);
+            }
+        }
+        assert_eq!(Some(lambda), (1..n.max(2)).filter_map(|a| multiplicative_order(a, n)).max(), "{}", n);
+
+        let root = primitive_root(n);
+        // 1, 2, 4, p^k and 2p^k for odd primes p
+        let odd_part = if n % 4 == 2 { n / 2 } else { n };
+        let has_root = n <= 4
+            || (odd_part % 2 == 1 && Primes::default().factorise(odd_part).factors.count_distinct_prime_factors() == 1);
+        assert_eq!(root.is_some(), has_root, "{}", n);
+        if let (Some(g), true) = (root, n > 1) {
+            assert_eq!(multiplicative_order(g, n), Some(lambda));
+        }
+    }
+
+    assert_eq!(carmichael_lambda(561), 80);
+    assert_eq!(primitive_root(7), Some(3));
+    assert_eq!(primitive_root(1_000_000_007), Some(5));
+    assert_eq!(primitive_root(u64::MAX - 58).map(|g| multiplicative_order(g, u64::MAX - 58)), Some(Some(u64::MAX - 59)));
+    assert_eq!(multiplicative_order(10, 983), Some(982));
+    assert_eq!(multiplicative_order(2, u64::MAX), Some(64));
+}
+
+#[test]
+fn discrete_logarithms() {
+    for n in 1..120u64 {
+        for a in 0..n {
+            // the first power hitting each residue
+            let mut first = std::collections::HashMap::new();
+            let mut x = 1 % n;
+            for k in 0..2 * n {
+                first.entry(x).or_insert(k);
+                x = x * a % n;
+            }
+            for b in 0..n {
+                assert_eq!(discrete_log(a, b, n), first.get(&b).copied(), "log_{} {} mod {}", a, b, n);
+            }
+        }
+    }
+
+    let p = 1_000_000_007;
+    let x = 123_456_789;
+    assert_eq!(discrete_log(5, mod_pow(5, x, p), p), Some(x));
+    let p = 1_000_000_000_039;
+    let g = primitive_root(p).unwrap();
+    assert_eq!(discrete_log(g, mod_pow(g, 987_654_321_012, p), p), Some(987_654_321_012));
+}
+
+#[test]
+fn residue_symbols() {
+    let ps = Primes::up_to(2000);
+    let small_primes: Vec<u64> = ps.iter().skip(1).take_while(|&p| p < 2000).collect();
+
+    for &p in &small_primes {
+        for a in 0..p {
+            // Euler's criterion
+            let euler = match mod_pow(a, (p - 1) / 2, p) {
+                0 => 0,
+                1 => 1,
+                _ => -1,
+            };
+            assert_eq!(legendre(a, p), euler, "({}/{})", a, p);
+        }
+    }
+
+    // the Jacobi symbol is the product of the Legendre symbols of the prime factors
+    for n in (1..600).step_by(2) {
+        let fs = ps.factorise(n).factors;
+        for a in 0..200 {
+            let expected: i8 = fs.iter().map(|f| legendre(a, f.prime).pow(f.power)).product();
+            assert_eq!(jacobi(a, n), expected, "({}/{})", a, n);
+        }
+    }
+    assert_eq!(jacobi(1001, 9907), -1);
+    assert_eq!(jacobi(19, 45), 1);
+}
+
+#[test]
+fn square_roots_modulo_primes() {
+    let primes: Vec<u64> = Primes::default().iter().take_while(|&p| p < 1000).collect();
+    for &p in &primes {
+        let squares: std::collections::HashSet<u64> = (0..p).map(|x| x * x % p).collect();
+        for a in 0..p {
+            let ts = tonelli_shanks(a, p);
+            let c = cipolla(a, p);
+            assert_eq!(ts.is_some(), squares.contains(&a), "{} mod {}", a, p);
+            assert_eq!(ts, c, "{} mod {}", a, p);
+            if let Some(r) = ts {
+                assert_eq!(r * r % p, a);
+                assert!(r <= p - r || r == 0);
+            }
+        }
+    }
+
+    // p - 1 has a large power of two in some of these
+    for &p in &[998_244_353u64, 1_000_000_007, u64::MAX - 58, (1 << 61) - 1] {
+        for a in (1..2000u64).map(|x| mod_mul(x, x, p) ^ 1) {
+            let ts = tonelli_shanks(a, p);
+            assert_eq!(ts, cipolla(a, p));
+            assert_eq!(ts.is_some(), legendre(a, p) != -1);
+            if let Some(r) = ts {
+                assert_eq!(mod_mul(r, r, p), a % p);
+            }
+        }
+    }
+}
+
+#[test]
+fn square_roots_modulo_composites() {
+    for n in 1..=400u64 {
+        for a in 0..n {
+            let expected: Vec<u64> = (0..n).filter(|x| x * x % n == a).collect();
+            assert_eq!(sqrt_mod(a, n), expected, "sqrt {} mod {}", a, n);
+        }
+    }
+
+    for (p, e) in [(2u64, 10u32), (3, 7), (5, 5), (7, 4), (2, 1)] {
+        let pe = p.pow(e);
+        for a in 0..pe {
+            let expected: Vec<u64> = (0..pe).filter(|x| x * x % pe == a).collect();
+            assert_eq!(sqrt_mod_prime_power(a, p, e), expected, "sqrt {} mod {}^{}", a, p, e);
+        }
+    }
+
+    // 2^64 - 1 has seven prime factors, each giving two roots of 4
+    let roots = sqrt_mod(4, u64::MAX);
+    assert_eq!(roots.len(), 128);
+    assert!(roots.iter().all(|&r| mod_mul(r, r, u64::MAX) == 4));
+    assert_eq!(roots[0], 2);
+}
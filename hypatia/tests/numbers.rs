@@ -0,0 +1,312 @@
+use hypatia::decimal::Decimal;
+use hypatia::numbers::*;
+
+#[test]
+fn integer_roots_are_exact() {
+    for n in (0..10_000u64).chain((0..64).map(|b| 1u64 << b)).chain(u64::MAX - 10..=u64::MAX) {
+        let r = isqrt(n);
+        assert!(r * r <= n && (r + 1).checked_mul(r + 1).is_none_or(|s| s > n), "isqrt({})", n);
+        let c = icbrt(n);
+        assert!(c.pow(3) <= n && (c + 1).checked_pow(3).is_none_or(|s| s > n), "icbrt({})", n);
+    }
+
+    // well past 2^53, where f64 can no longer tell the difference
+    let big = u64::from(u32::MAX);
+    assert_eq!(isqrt(big * big - 1), big - 1);
+    assert_eq!(isqrt(u64::MAX), u64::from(u32::MAX));
+    assert_eq!(isqrt_u128(u128::MAX), u128::from(u64::MAX));
+    assert_eq!(icbrt_u128(u128::MAX), 6_981_463_658_331);
+    assert_eq!(nth_root(u64::MAX, 64), 1);
+    assert_eq!(nth_root(1 << 63, 63), 2);
+    assert_eq!(nth_root(3u64.pow(40), 40), 3);
+    assert_eq!(nth_root(3u64.pow(40) - 1, 40), 2);
+}
+
+#[test]
+fn perfect_squares_and_powers() {
+    let squares: Vec<u64> = (0..100).filter(|&n| is_perfect_square(n)).collect();
+    assert_eq!(squares, vec![0, 1, 4, 9, 16, 25, 36, 49, 64, 81]);
+
+    let powers: Vec<u64> = (0..100).filter(|&n| is_perfect_power(n)).collect();
+    assert_eq!(powers, vec![0, 1, 4, 8, 9, 16, 25, 27, 32, 36, 49, 64, 81]);
+    assert!(is_perfect_power(3u64.pow(40)));
+    assert!(!is_perfect_power(u64::MAX));
+}
+
+#[test]
+fn fibonacci_indexing_agrees() {
+    let fs: Vec<u64> = fib().take(10).collect();
+    assert_eq!(fs, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+
+    for (n, f) in fib().enumerate() {
+        assert_eq!(nth_fib(n as u64), Some(f));
+    }
+    assert_eq!(nth_fib(93), Some(12_200_160_415_121_876_738));
+    assert_eq!(nth_fib(94), None);
+    assert_eq!(nth_fib(u64::MAX), None);
+    // the same index works for both
+    for n in [0u64, 1, 50, 93] {
+        assert_eq!(nth_fib(n).map(Decimal::from), Some(Decimal::nth_fib(n)));
+    }
+}
+
+#[test]
+fn lucas_sequences() {
+    let take = |it: Box<dyn Iterator<Item = Decimal>>| it.take(8).map(|d| d.to_string()).collect::<Vec<_>>().join(",");
+
+    assert_eq!(take(Box::new(Lucas::fibonacci().u())), "0,1,1,2,3,5,8,13");
+    assert_eq!(take(Box::new(Lucas::fibonacci().v())), "2,1,3,4,7,11,18,29");
+    assert_eq!(take(Box::new(Lucas::pell().u())), "0,1,2,5,12,29,70,169");
+    assert_eq!(take(Box::new(Lucas::pell().v())), "2,2,6,14,34,82,198,478");
+
+    for &(p, q) in &[(1, -1), (2, -1), (3, 2), (-2, 5), (4, 4)] {
+        let l = Lucas::new(p, q);
+        for (n, (u, v)) in l.u().zip(l.v()).take(40).enumerate() {
+            assert_eq!(l.nth_u(n as u64), u, "U_{}({}, {})", n, p, q);
+            assert_eq!(l.nth_v(n as u64), v, "V_{}({}, {})", n, p, q);
+        }
+    }
+
+    assert_eq!(Lucas::fibonacci().nth_u(1000), Decimal::nth_fib(1000));
+}
+
+#[test]
+fn sieve_matches_trial_division() {
+    let is_prime = |n: u64| n > 1 && (2..).take_while(|d| d * d <= n).all(|d| n % d != 0);
+    let expected: Vec<u64> = (0..300_000).filter(|&n| is_prime(n)).collect();
+
+    let actual: Vec<u64> = Primes::default().iter().take(expected.len()).collect();
+    assert_eq!(actual, expected);
+
+    let ps = Primes::up_to(1_000_000);
+    assert_eq!(ps.iter().take_while(|&p| p <= 1_000_000).count(), 78_498);
+    assert_eq!(Primes::with_capacity_up_to(100).iter().nth(25), Some(101));
+}
+
+#[test]
+fn primes_shared_between_threads() {
+    let ps = Primes::default();
+    let counts: Vec<usize> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..4)
+            .map(|_| s.spawn(|| ps.iter().take_while(|&p| p < 500_000).count()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(counts, vec![41_538; 4]);
+
+    let f = Primes::global().factorise(2 * 2 * 3 * 999_983);
+    assert_eq!(f.factors.count_divisors(), 12);
+}
+
+#[test]
+fn miller_rabin_matches_sieve() {
+    let ps = Primes::up_to(1_000_000);
+    let mut primes = ps.iter().peekable();
+    for n in 0..1_000_000 {
+        let expected = primes.next_if_eq(&n).is_some();
+        assert_eq!(is_prime(n), expected, "{}", n);
+    }
+
+    // strong pseudoprimes to many small bases
+    assert!(!is_prime(3_215_031_751));
+    assert!(!is_prime(3_825_123_056_546_413_051));
+    assert!(!is_prime(561));
+    // strong pseudoprime to the bases 2..=37, but not 41
+    assert!(!is_probable_prime_u128(318_665_857_834_031_151_167_461));
+
+    assert!(is_prime((1 << 61) - 1));
+    assert!(is_prime(u64::MAX - 58));
+    assert!(!is_prime(u64::MAX));
+
+    // products of two large primes
+    let p = 4_294_967_291u64;
+    let q = 4_294_967_279u64;
+    assert!(is_prime(p) && is_prime(q));
+    assert!(!is_prime(p * q));
+    assert!(!is_probable_prime_u128(u128::from(p) * u128::from(u64::MAX - 58)));
+
+    assert!(is_probable_prime_u128((1 << 89) - 1));
+    assert!(is_probable_prime_u128((1 << 127) - 1));
+    assert!(!is_probable_prime_u128((1 << 127) + 1));
+    assert!(!is_probable_prime_u128(u128::MAX));
+}
+
+#[test]
+fn decimal_probable_primes() {
+    let two = Decimal::from(2u32);
+    let one = Decimal::from(1u32);
+    let mersenne = |p: u32| &two.pow(p) - &one;
+
+    assert!(mersenne(521).is_probable_prime());
+    assert!(mersenne(607).is_probable_prime());
+    assert!(!mersenne(523).is_probable_prime());
+    assert!(!(&mersenne(127) * &mersenne(89)).is_probable_prime());
+
+    for n in 0..1_000u64 {
+        assert_eq!(Decimal::from(n).is_probable_prime(), is_prime(n));
+    }
+    assert!(!Decimal::from(-7).is_probable_prime());
+}
+
+#[test]
+fn pollard_rho_factorisation() {
+    let ps = Primes::default();
+    let powers = |f: &Factorisation| -> Vec<(u64, u32)> { f.factors.iter().map(|f| (f.prime, f.power)).collect() };
+
+    assert_eq!(powers(&ps.factorise(600_851_475_143)), vec![(71, 1), (839, 1), (1471, 1), (6857, 1)]);
+    assert_eq!(
+        powers(&ps.factorise(u64::MAX)),
+        vec![(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65537, 1), (6_700_417, 1)]);
+
+    let p = 4_294_967_291u64;
+    let q = 4_294_967_279u64;
+    assert_eq!(powers(&ps.factorise(p * q)), vec![(q, 1), (p, 1)]);
+    assert_eq!(powers(&ps.factorise(p * p)), vec![(p, 2)]);
+    assert_eq!(powers(&ps.factorise(u64::MAX - 58)), vec![(u64::MAX - 58, 1)]);
+    assert_eq!(powers(&ps.factorise(1_000_003 * 1_000_003 * 1_000_003)), vec![(1_000_003, 3)]);
+
+    assert!(ps.factorise(0).factors.iter().next().is_none());
+    assert!(ps.factorise(1).factors.iter().next().is_none());
+
+    // the same answers with and without trial division
+    for n in 2..20_000 {
+        let f = ps.factorise_with_trial_bound(n, 0);
+        assert_eq!(powers(&f), powers(&ps.factorise(n)), "{}", n);
+        assert_eq!(f.factors.factorisation().n, n);
+    }
+}
+
+#[test]
+fn divisor_enumeration() {
+    let ps = Primes::default();
+    let gcd = |a: u64, b: u64| (1..=a.min(b)).rev().find(|d| a % d == 0 && b % d == 0).unwrap();
+
+    for n in 1..=2000u64 {
+        let fs = ps.factorise(n).factors;
+        let expected: Vec<u64> = (1..=n).filter(|d| n % d == 0).collect();
+
+        let mut unsorted: Vec<u64> = fs.divisors().collect();
+        unsorted.sort_unstable();
+        assert_eq!(unsorted, expected, "{}", n);
+        assert_eq!(fs.sorted_divisors().collect::<Vec<_>>(), expected, "{}", n);
+        assert_eq!(fs.proper_divisors().collect::<Vec<_>>(), expected[..expected.len() - 1], "{}", n);
+
+        let unitary: Vec<u64> = expected.iter().copied().filter(|&d| gcd(d, n / d) == 1).collect();
+        assert_eq!(fs.unitary_divisors().collect::<Vec<_>>(), unitary, "{}", n);
+
+        let squares: Vec<u64> = expected.iter().copied().filter(|&d| is_perfect_square(d)).collect();
+        assert_eq!(fs.square_divisors().collect::<Vec<_>>(), squares, "{}", n);
+
+        let pairs: Vec<(u64, u64)> = expected.iter().copied().filter(|&d| d * d <= n).map(|d| (d, n / d)).collect();
+        assert_eq!(fs.divisor_pairs().collect::<Vec<_>>(), pairs, "{}", n);
+    }
+
+    assert_eq!(ps.factorise(36).factors.divisor_pairs().last(), Some((6, 6)));
+    assert_eq!(ps.factorise(720_720).factors.divisors().count(), 240);
+}
+
+#[test]
+fn sequences_stop_before_overflow() {
+    assert_eq!(fib::<u64>().count(), 94);
+    assert_eq!(fib::<u64>().last(), Some(12_200_160_415_121_876_738));
+    assert_eq!(factorial::<u64>().count(), 21);
+    assert_eq!(factorial::<u64>().last(), Some(2_432_902_008_176_640_000));
+
+    assert_eq!(triangular(100u64), 5050);
+    assert_eq!(triangular(6_074_000_999u64), 18_446_744_070_963_499_500);
+    assert_eq!(checked_triangular(6_074_001_000u64), None);
+    assert_eq!(checked_triangular(u64::MAX), None);
+    assert_eq!(checked_triangular(0u64), Some(0));
+}
+
+#[test]
+fn divisor_sums_never_wrap() {
+    let f = Factor { prime: 2u64, power: 63 };
+    assert_eq!(f.power_sum(), u64::MAX);
+    assert_eq!(f.calculate(), 1 << 63);
+
+    let f = Factor { prime: 2u64, power: 64 };
+    assert_eq!(f.checked_power_sum(), None);
+    assert_eq!(f.widen().checked_power_sum(), Some((1 << 65) - 1));
+    assert_eq!(f.checked_calculate(), None);
+    assert_eq!(f.widen().checked_calculate(), Some(1 << 64));
+
+    // the largest highly composite number that fits in a u64
+    let n = 18_401_055_938_125_660_800;
+    let fs = Primes::default().factorise(n);
+    assert_eq!(fs.factors.checked_sum_divisors(), None);
+    assert_eq!(fs.factors.sum_divisors_u128(), 121_252_093_161_357_312_000);
+    assert_eq!(fs.sum_proper_divisors_u128(), 121_252_093_161_357_312_000 - u128::from(n));
+    assert_eq!(fs.perfection(), Perfection::Abundant);
+
+    let fs = Primes::default().factorise(u64::MAX);
+    assert_eq!(fs.factors.sum_divisors_u128(), 31_421_980_989_189_888_768);
+    assert_eq!(fs.perfection(), Perfection::Deficient);
+    assert_eq!(fs.factors.checked_product(), Some(u64::MAX));
+}
+
+#[test]
+#[should_panic(expected = "overflows")]
+fn sum_divisors_panics_on_overflow() {
+    Primes::default().factorise(18_401_055_938_125_660_800).factors.sum_divisors();
+}
+
+#[test]
+fn generic_over_integer_types() {
+    assert_eq!(triangular(22u8), 253);
+    assert_eq!(checked_triangular(23u8), None);
+    assert_eq!(triangular(Decimal::from(10u64.pow(12))), "500000000000500000000000".parse().unwrap());
+
+    assert_eq!(fib::<u8>().count(), 14);
+    assert_eq!(fib::<u128>().count(), 187);
+    assert!(fib::<Decimal>().take(300).eq(Decimal::fib().take(300)));
+    assert_eq!(factorial::<u32>().count(), 13);
+    assert_eq!(factorial::<u128>().count(), 35);
+    assert_eq!(factorial::<Decimal>().nth(25), Some("15511210043330985984000000".parse().unwrap()));
+
+    assert!(is_palendrome(9009u16));
+    assert!(is_palendrome("12345678987654321012345678987654321".parse::<Decimal>().unwrap()));
+    assert!(!is_palendrome(Decimal::from(10u32)));
+
+    // Decimal is signed, so only the primitives stop at zero
+    fn checked_diff<T: Natural>(a: T, b: T) -> Option<T> {
+        a.checked_sub(&b)
+    }
+    assert_eq!(checked_diff(3u8, 5), None);
+    assert_eq!(checked_diff(Decimal::from(3u32), Decimal::from(5u32)), Some(Decimal::from(-2i64)));
+    assert_eq!(checked_diff(Decimal::from(-5i64), Decimal::from(1u32)), Some(Decimal::from(-6i64)));
+    assert_eq!(checked_diff(Decimal::from(5u32), Decimal::from(3u32)), Some(Decimal::from(2u32)));
+
+    // primes to the power zero are dropped
+    let fs = Factors::new(vec![Factor { prime: 2u64, power: 0 }, Factor { prime: 3, power: 1 }]);
+    assert_eq!(fs.iter().count(), 1);
+    assert_eq!(fs.totient(), 2);
+    assert_eq!(fs.mobius(), -1);
+    assert_eq!(fs.count_divisors(), 2);
+
+    // 2^100 * 3^2 * 7
+    let fs = Factors::new(vec![
+        Factor { prime: Decimal::from(7u32), power: 1 },
+        Factor { prime: Decimal::from(2u32), power: 60 },
+        Factor { prime: Decimal::from(3u32), power: 2 },
+        Factor { prime: Decimal::from(2u32), power: 40 },
+    ]);
+    let n = &Decimal::from(2u32).pow(100) * &Decimal::from(63u32);
+    assert_eq!(fs.count_divisors(), 101 * 3 * 2);
+    assert_eq!(fs.sum_divisors(), &(&Decimal::from(2u32).pow(101) - &Decimal::from(1u32)) * &Decimal::from(13 * 8u32));
+    assert_eq!(fs.totient(), &Decimal::from(2u32).pow(99) * &Decimal::from(36u32));
+    assert_eq!(fs.divisors().count(), 606);
+    assert_eq!(fs.sorted_divisors().last(), Some(n.clone()));
+    assert_eq!(fs.factorisation().perfection(), Perfection::Abundant);
+
+    let wide = Primes::default().factorise(u64::MAX).factors.widen();
+    assert_eq!(wide.sigma(1), 31_421_980_989_189_888_768);
+    assert_eq!(wide.totient(), u128::from(Primes::default().factorise(u64::MAX).factors.totient()));
+}
+
+#[test]
+fn primes_as_u32() {
+    let ps = Primes::default();
+    assert!(ps.iter_u32().take(10_000).map(u64::from).eq(ps.iter().take(10_000)));
+    assert_eq!(ps.iter_u32().nth(9_999), Some(104_729));
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use hypatia::pythagorean::{
+    euclid_triples, perimeter_counts, primitive_triples, triples, triples_with_perimeter, Limit, Triple,
+};
+
+fn brute_force(limit: Limit) -> BTreeSet<Triple> {
+    let mut ts = BTreeSet::new();
+    for c in 1..=250u64 {
+        for b in 1..c {
+            for a in 1..b {
+                let t = Triple { a, b, c };
+                if a * a + b * b == c * c && limit.admits(&t) {
+                    ts.insert(t);
+                }
+            }
+        }
+    }
+    ts
+}
+
+#[test]
+fn triple_basics() {
+    let t = Triple::euclid(2, 1);
+    assert_eq!(t, Triple { a: 3, b: 4, c: 5 });
+    assert_eq!(t.to_string(), "(3, 4, 5)");
+    assert_eq!(t.perimeter(), 12);
+    assert_eq!(t.product(), 60);
+    assert!(t.is_primitive());
+    assert!(!t.scale(3).is_primitive());
+
+    assert_eq!(Triple::euclid(4, 1), Triple { a: 8, b: 15, c: 17 });
+    assert_eq!(
+        t.children(),
+        [
+            Triple { a: 5, b: 12, c: 13 },
+            Triple { a: 20, b: 21, c: 29 },
+            Triple { a: 8, b: 15, c: 17 },
+        ]
+    );
+}
+
+#[test]
+fn generators_agree() {
+    for &limit in &[Limit::Hypotenuse(200), Limit::Perimeter(450), Limit::Hypotenuse(4), Limit::Perimeter(11)] {
+        let all = brute_force(limit);
+        let primitive: BTreeSet<_> = all.iter().copied().filter(Triple::is_primitive).collect();
+
+        let berggren: Vec<_> = primitive_triples(limit).collect();
+        let euclid: Vec<_> = euclid_triples(limit).collect();
+        assert_eq!(berggren.len(), primitive.len(), "{:?}", limit);
+        assert_eq!(euclid.len(), primitive.len(), "{:?}", limit);
+        assert_eq!(berggren.into_iter().collect::<BTreeSet<_>>(), primitive, "{:?}", limit);
+        assert_eq!(euclid.into_iter().collect::<BTreeSet<_>>(), primitive, "{:?}", limit);
+
+        let every: Vec<_> = triples(limit).collect();
+        assert_eq!(every.len(), all.len(), "{:?}", limit);
+        assert_eq!(every.into_iter().collect::<BTreeSet<_>>(), all, "{:?}", limit);
+    }
+
+    assert_eq!(primitive_triples(Limit::Hypotenuse(100)).count(), 16);
+}
+
+#[test]
+fn triples_by_perimeter() {
+    // Problem 9
+    assert_eq!(triples_with_perimeter(1000), vec![Triple { a: 200, b: 375, c: 425 }]);
+    assert_eq!(
+        triples_with_perimeter(120),
+        vec![Triple { a: 20, b: 48, c: 52 }, Triple { a: 24, b: 45, c: 51 }, Triple { a: 30, b: 40, c: 50 }]
+    );
+    assert!(triples_with_perimeter(121).is_empty());
+
+    let counts = perimeter_counts(450);
+    let all = brute_force(Limit::Perimeter(450));
+    for (p, &count) in counts.iter().enumerate() {
+        assert_eq!(count as usize, triples_with_perimeter(p as u64).len(), "p = {}", p);
+        assert_eq!(count as usize, all.iter().filter(|t| t.perimeter() == p as u64).count());
+    }
+
+    // Problem 39
+    let counts = perimeter_counts(1000);
+    let best = (0..counts.len()).max_by_key(|&p| counts[p]).unwrap();
+    assert_eq!(best, 840);
+
+    // Problem 75
+    let unique = perimeter_counts(1_500_000).into_iter().filter(|&c| c == 1).count();
+    assert_eq!(unique, 161_667);
+}
@@ -0,0 +1,53 @@
+use hypatia::decimal::Decimal;
+use hypatia::rational::{ParseRationalError, Rational};
+
+fn r(s: &str) -> Rational {
+    s.parse().unwrap()
+}
+
+#[test]
+fn kept_in_lowest_terms() {
+    let a = Rational::from_fraction(6, -8);
+    assert_eq!(a.numer(), &Decimal::from(-3));
+    assert_eq!(a.denom(), &Decimal::from(4));
+    assert_eq!(a, r("-3/4"));
+    assert_eq!(Rational::from_fraction(0, -5), Rational::zero());
+    assert_eq!(r("10/5").to_string(), "2");
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(&r("1/2") + &r("1/3"), r("5/6"));
+    assert_eq!(&r("1/2") - &r("2/3"), r("-1/6"));
+    assert_eq!(&r("4/9") * &r("-3/8"), r("-1/6"));
+    assert_eq!(&r("4/9") / &r("2/3"), r("2/3"));
+    assert_eq!(-r("1/2"), r("-1/2"));
+    assert_eq!(r("2/3").pow(-2), r("9/4"));
+    assert_eq!(r("-7/2").floor(), Decimal::from(-4));
+    assert_eq!(r("-7/2").trunc(), Decimal::from(-3));
+
+    let mut x = r("1/4");
+    x += r("1/4");
+    x *= &r("4");
+    assert_eq!(x, r("2"));
+
+    // the harmonic number H_10
+    let h: Rational = (1..=10).map(|n| Rational::from_fraction(1, n)).sum();
+    assert_eq!(h, r("7381/2520"));
+}
+
+#[test]
+fn ordering() {
+    let mut rs = vec![r("1/2"), r("-1/3"), r("2/5"), r("0"), r("-1/2")];
+    rs.sort();
+    assert_eq!(rs, vec![r("-1/2"), r("-1/3"), r("0"), r("2/5"), r("1/2")]);
+}
+
+#[test]
+fn parsing() {
+    assert_eq!(r(" -3 / 6 "), Rational::from_fraction(-1, 2));
+    assert_eq!("1/0".parse::<Rational>(), Err(ParseRationalError::ZeroDenominator));
+    assert!(matches!("x/2".parse::<Rational>(), Err(ParseRationalError::Numerator(_))));
+    assert!(matches!("1/".parse::<Rational>(), Err(ParseRationalError::Denominator(_))));
+    assert_eq!(r("1/6").expansion().to_string(), "0.1(6)");
+}
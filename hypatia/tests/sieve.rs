@@ -0,0 +1,88 @@
+use hypatia::numbers::{Factorisation, Primes};
+use hypatia::sieve::*;
+
+fn powers(f: &Factorisation) -> Vec<(u64, u32)> {
+    f.factors.iter().map(|f| (f.prime, f.power)).collect()
+}
+
+#[test]
+fn factor_sieve_matches_factorise() {
+    let sieve = FactorSieve::up_to(100_000);
+    let primes = Primes::default();
+
+    assert_eq!(sieve.limit(), 100_000);
+    assert_eq!(sieve.primes().count(), 9592);
+    assert!(sieve.primes().eq(primes.iter().take(9592)));
+
+    for n in 0..=100_000 {
+        assert_eq!(powers(&sieve.factorise(n)), powers(&primes.factorise(n)), "{}", n);
+        assert_eq!(sieve.is_prime(n), hypatia::numbers::is_prime(n), "{}", n);
+    }
+    assert_eq!(sieve.smallest_factor(1), None);
+    assert_eq!(sieve.smallest_factor(91), Some(7));
+}
+
+#[test]
+fn factor_sieve_ranges() {
+    let sieve = FactorSieve::up_to(1000);
+
+    let ns: Vec<u64> = sieve.factorisations(10..20).map(|(n, _)| n).collect();
+    assert_eq!(ns, (10..20).collect::<Vec<_>>());
+    assert!(sieve.factorisations(..).all(|(n, f)| f.n == n));
+    assert_eq!(sieve.factorisations(990..).count(), 11);
+    assert_eq!(sieve.factorisations(0..0).count(), 0);
+
+    let (n, f) = sieve.factorisations(720..=720).next().unwrap();
+    assert_eq!(n, 720);
+    assert_eq!(powers(&f), vec![(2, 4), (3, 2), (5, 1)]);
+}
+
+#[test]
+#[should_panic]
+fn factor_sieve_range_past_limit() {
+    FactorSieve::up_to(1000).factorisations(..=1001).count();
+}
+
+#[test]
+fn arithmetic_function_tables() {
+    let limit = 10_000;
+    let sieve = FactorSieve::up_to(limit);
+
+    let phi = totients(limit);
+    let mu = mobius(limit);
+    let sigma_1 = sigmas(1, limit);
+    let sigma_2 = sigmas(2, limit);
+    let d = divisor_counts(limit);
+    let omega = distinct_prime_factor_counts(limit);
+    let big_omega = prime_factor_counts(limit);
+
+    assert_eq!((phi[0], mu[0], sigma_1[0], d[0], omega[0], big_omega[0]), (0, 0, 0, 0, 0, 0));
+
+    for (n, f) in sieve.factorisations(1..=limit) {
+        let i = n as usize;
+        let fs = &f.factors;
+        assert_eq!(phi[i], fs.totient(), "{}", n);
+        assert_eq!(mu[i], fs.mobius(), "{}", n);
+        assert_eq!(sigma_1[i], fs.sigma(1), "{}", n);
+        assert_eq!(sigma_1[i], fs.sum_divisors(), "{}", n);
+        assert_eq!(sigma_2[i], fs.sigma(2), "{}", n);
+        assert_eq!(d[i], fs.count_divisors(), "{}", n);
+        assert_eq!(u64::from(d[i]), fs.sigma(0), "{}", n);
+        assert_eq!(u32::from(omega[i]), fs.count_distinct_prime_factors(), "{}", n);
+        assert_eq!(u32::from(big_omega[i]), fs.count_prime_factors(), "{}", n);
+    }
+
+    assert_eq!(phi[36], 12);
+    assert_eq!(mu[30], -1);
+    assert_eq!(mu[12], 0);
+    assert_eq!(sigma_1[28], 56);
+    assert_eq!(sigma_2[6], 50);
+    assert_eq!(big_omega[1024], 10);
+    assert_eq!(mobius(0), vec![0]);
+    assert_eq!(mobius(1), vec![0, 1]);
+
+    // the number of reduced proper fractions with denominators up to a million
+    assert_eq!(totients(1_000_000).iter().skip(2).sum::<u64>(), 303_963_552_391);
+    // the Mertens function at a million
+    assert_eq!(mobius(1_000_000).iter().map(|&m| i64::from(m)).sum::<i64>(), 212);
+}